@@ -0,0 +1,596 @@
+//! Memory-constrained, windowed processing of the accumulator.
+//!
+//! [`crate::Accumulator::transform`] and the serialization path in `main`
+//! hold every point of the accumulator in RAM at once (`tau_powers_g1` alone
+//! has `params.tau_powers_g1_length` points). That's fine for a toy `2^5`
+//! ceremony, but infeasible once the circuit grows to a realistic (2^26-2^28
+//! point) size. `BatchedAccumulator` performs the same transformation -- and
+//! the same `power_pairs`/`merge_pairs` consistency checks that
+//! `verify_transform` relies on -- a fixed-size `batch_size` window at a
+//! time, reading the challenge through an `Mmap` and writing the response
+//! through an `MmapMut`, so peak memory is bounded by `batch_size` rather
+//! than by the size of the ceremony. Generic over the pairing `P`, same as
+//! `Accumulator` itself -- see the `bls12_381_compiles` test below for proof
+//! this isn't just generic in name.
+
+use crate::{batch_exp, merge_pairs, same_ratio, CeremonyParams, PrivateKey, UseCompression};
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::fields::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+use blake2::{Blake2b512, Digest};
+use generic_array::GenericArray;
+use memmap2::{Mmap, MmapMut};
+use std::io;
+use typenum::consts::U64;
+
+/// Byte offset and length (in points) of one of the accumulator's four
+/// sub-vectors within the on-disk challenge/response files. Sections are
+/// laid out back to back in the same order as the `Accumulator` struct
+/// fields, immediately after the 64-byte hash of the previous contribution.
+/// Each section is itself preceded by an 8-byte length prefix, because the
+/// fields are `Vec`s and `Accumulator`'s derived `CanonicalSerialize` writes
+/// one ahead of every `Vec` field (see `contribution_byte_size`'s `+ 32`,
+/// one 8-byte prefix per vector).
+pub(crate) struct Section {
+    offset: usize,
+    len: usize,
+    point_size: usize,
+}
+
+/// Walks a batched transform over the accumulator's four sub-vectors without
+/// ever holding more than `batch_size` points of any one of them in memory.
+pub struct BatchedAccumulator {
+    batch_size: usize,
+}
+
+impl BatchedAccumulator {
+    /// `batch_size` is the number of points processed (deserialized,
+    /// exponentiated, re-serialized) per window.
+    pub fn new(batch_size: usize) -> Self {
+        assert!(batch_size > 0, "batch_size must be positive");
+        BatchedAccumulator { batch_size }
+    }
+
+    /// Section layout for an accumulator on disk, computed from `params`'s
+    /// vector lengths plus the trailing `beta_g2`. The four sections have
+    /// different lengths (`tau_powers_g1_length` for the G1 tau powers,
+    /// `tau_powers_length` for everything else), so their byte offsets can't
+    /// be derived from a single stride. `point_size` depends on `compression`
+    /// since the challenge is always uncompressed but the response may not be.
+    ///
+    /// Each `Vec` field is preceded by an 8-byte length prefix (derived
+    /// `CanonicalSerialize` writes one per `Vec`, not once for the whole
+    /// struct), so every section's offset skips 8 bytes past the previous
+    /// one before the points themselves start. `beta_g2` is a lone point
+    /// field, not a `Vec`, so it gets no prefix of its own.
+    fn sections<P: Pairing>(
+        params: &CeremonyParams<P>,
+        compression: UseCompression,
+    ) -> (Section, Section, Section, Section, usize) {
+        let (g1_size, g2_size) = match compression {
+            UseCompression::No => (
+                P::G1Affine::generator().uncompressed_size(),
+                P::G2Affine::generator().uncompressed_size(),
+            ),
+            UseCompression::Yes => (
+                P::G1Affine::generator().compressed_size(),
+                P::G2Affine::generator().compressed_size(),
+            ),
+        };
+
+        // The 64-byte BLAKE2b hash of the previous contribution comes first.
+        let mut offset = 64;
+
+        offset += 8; // length prefix for tau_powers_g1
+        let tau_g1 = Section {
+            offset,
+            len: params.tau_powers_g1_length,
+            point_size: g1_size,
+        };
+        offset += tau_g1.len * tau_g1.point_size;
+
+        offset += 8; // length prefix for tau_powers_g2
+        let tau_g2 = Section {
+            offset,
+            len: params.tau_powers_length,
+            point_size: g2_size,
+        };
+        offset += tau_g2.len * tau_g2.point_size;
+
+        offset += 8; // length prefix for alpha_tau_powers_g1
+        let alpha_tau_g1 = Section {
+            offset,
+            len: params.tau_powers_length,
+            point_size: g1_size,
+        };
+        offset += alpha_tau_g1.len * alpha_tau_g1.point_size;
+
+        offset += 8; // length prefix for beta_tau_powers_g1
+        let beta_tau_g1 = Section {
+            offset,
+            len: params.tau_powers_length,
+            point_size: g1_size,
+        };
+        offset += beta_tau_g1.len * beta_tau_g1.point_size;
+
+        // beta_g2 is a lone point, not a batched vector, so it has no
+        // length prefix of its own.
+        (tau_g1, tau_g2, alpha_tau_g1, beta_tau_g1, offset)
+    }
+
+    /// Streams `input` (an uncompressed challenge, `Mmap`'d by the caller)
+    /// through `transform`, writing the result into `output` (an `MmapMut`
+    /// sized to hold a 64-byte header followed by the accumulator in
+    /// `output_compression`) a window at a time.
+    ///
+    /// Unlike the in-memory `Accumulator::transform`, this writes straight
+    /// into the `response` envelope: the header is `current_accumulator_hash`
+    /// (the BLAKE2b hash of the whole `input`, exactly what `HashReader`
+    /// would have produced had it read the challenge), not a copy of
+    /// `input`'s own header. That matches what `keypair` is run against, and
+    /// is what a verifier recomputes via `verify_transcript`.
+    ///
+    /// Returns `(current_accumulator_hash, output_hasher)`: `output_hasher`
+    /// has hashed everything written to `output` so far (header + transformed
+    /// accumulator), so the caller can keep feeding it the serialized public
+    /// key before finalizing -- exactly what `HashWriter` does in the
+    /// in-memory path -- to get the final contribution hash.
+    pub fn transform<P: Pairing>(
+        &self,
+        input: &Mmap,
+        output: &mut MmapMut,
+        key: &PrivateKey<P>,
+        params: &CeremonyParams<P>,
+        output_compression: UseCompression,
+    ) -> io::Result<(GenericArray<u8, U64>, Blake2b512)> {
+        let (in_tau_g1, in_tau_g2, in_alpha_tau_g1, in_beta_tau_g1, in_beta_g2_offset) =
+            Self::sections::<P>(params, UseCompression::No);
+        let (out_tau_g1, out_tau_g2, out_alpha_tau_g1, out_beta_tau_g1, out_beta_g2_offset) =
+            Self::sections::<P>(params, output_compression);
+
+        let current_accumulator_hash = {
+            let mut hasher = Blake2b512::default();
+            hasher.update(&input[..]);
+            hasher.finalize()
+        };
+
+        let mut output_hasher = Blake2b512::default();
+        output_hasher.update(current_accumulator_hash.as_slice());
+        output[0..64].copy_from_slice(current_accumulator_hash.as_slice());
+
+        // Each section's 8-byte length prefix has to be written (and folded
+        // into `output_hasher`) immediately before that section's points, in
+        // the same order a verifier would encounter them reading the file
+        // sequentially -- not all up front, which would hash them out of
+        // order relative to the file's actual byte layout.
+        let write_length_prefix = |output: &mut MmapMut, hasher: &mut Blake2b512, section: &Section| {
+            let prefix = (section.len as u64).to_le_bytes();
+            hasher.update(prefix);
+            output[section.offset - 8..section.offset].copy_from_slice(&prefix);
+        };
+
+        write_length_prefix(output, &mut output_hasher, &out_tau_g1);
+        self.transform_section::<P::G1Affine>(
+            input,
+            output,
+            &in_tau_g1,
+            &out_tau_g1,
+            output_compression,
+            key.tau,
+            None,
+            &mut output_hasher,
+        )?;
+        write_length_prefix(output, &mut output_hasher, &out_tau_g2);
+        self.transform_section::<P::G2Affine>(
+            input,
+            output,
+            &in_tau_g2,
+            &out_tau_g2,
+            output_compression,
+            key.tau,
+            None,
+            &mut output_hasher,
+        )?;
+        write_length_prefix(output, &mut output_hasher, &out_alpha_tau_g1);
+        self.transform_section::<P::G1Affine>(
+            input,
+            output,
+            &in_alpha_tau_g1,
+            &out_alpha_tau_g1,
+            output_compression,
+            key.tau,
+            Some(&key.alpha),
+            &mut output_hasher,
+        )?;
+        write_length_prefix(output, &mut output_hasher, &out_beta_tau_g1);
+        self.transform_section::<P::G1Affine>(
+            input,
+            output,
+            &in_beta_tau_g1,
+            &out_beta_tau_g1,
+            output_compression,
+            key.tau,
+            Some(&key.beta),
+            &mut output_hasher,
+        )?;
+
+        // beta_g2 is a single point, so there's nothing to batch.
+        let in_g2_size = P::G2Affine::generator().uncompressed_size();
+        let mut cursor = &input[in_beta_g2_offset..in_beta_g2_offset + in_g2_size];
+        let beta_g2 = P::G2Affine::deserialize_with_mode(&mut cursor, Compress::No, Validate::No)
+            .expect("unable to read beta_g2 from challenge");
+        let beta_g2 = (beta_g2 * key.beta).into_affine();
+
+        let mut buf = Vec::new();
+        beta_g2
+            .serialize_with_mode(&mut buf, output_compression.into())
+            .expect("unable to serialize beta_g2");
+        output_hasher.update(&buf);
+        output[out_beta_g2_offset..out_beta_g2_offset + buf.len()].copy_from_slice(&buf);
+
+        Ok((current_accumulator_hash, output_hasher))
+    }
+
+    /// Processes a single section (e.g. `tau_powers_g1`) window by window:
+    /// deserialize `batch_size` points out of the `Mmap`'d input (always
+    /// uncompressed, per `in_section`), exponentiate them with `batch_exp`,
+    /// and write the result into the `MmapMut`'d output per `out_section` and
+    /// `output_compression`, folding the serialized bytes into `hasher` as we go.
+    ///
+    /// Each window's exponents are `tau^processed, tau^(processed + 1), ...`:
+    /// seeded once per window with a single `Field::pow`, then advanced by one
+    /// incremental multiplication by `tau` per point, the same chunking
+    /// strategy `Accumulator::transform` uses -- not a fresh `pow` per point,
+    /// which would cost `O(log i)` field multiplications each instead of `O(1)`.
+    #[allow(clippy::too_many_arguments)]
+    fn transform_section<G: AffineRepr>(
+        &self,
+        input: &Mmap,
+        output: &mut MmapMut,
+        in_section: &Section,
+        out_section: &Section,
+        output_compression: UseCompression,
+        tau: G::ScalarField,
+        coeff: Option<&G::ScalarField>,
+        hasher: &mut Blake2b512,
+    ) -> io::Result<()> {
+        let mut processed = 0;
+        while processed < in_section.len {
+            let window = self.batch_size.min(in_section.len - processed);
+            let in_byte_start = in_section.offset + processed * in_section.point_size;
+            let in_byte_len = window * in_section.point_size;
+
+            let mut points = Vec::with_capacity(window);
+            let mut cursor = &input[in_byte_start..in_byte_start + in_byte_len];
+            for _ in 0..window {
+                points.push(
+                    G::deserialize_with_mode(&mut cursor, Compress::No, Validate::No)
+                        .expect("unable to deserialize a window of the challenge"),
+                );
+            }
+
+            let mut power = tau.pow([processed as u64]);
+            let exponents: Vec<G::ScalarField> = (0..window)
+                .map(|_| {
+                    let e = power;
+                    power *= tau;
+                    e
+                })
+                .collect();
+            batch_exp(&mut points, &exponents, coeff);
+
+            let mut buf = Vec::with_capacity(window * out_section.point_size);
+            for point in &points {
+                point
+                    .serialize_with_mode(&mut buf, output_compression.into())
+                    .expect("unable to serialize a transformed window");
+            }
+            hasher.update(&buf);
+
+            let out_byte_start = out_section.offset + processed * out_section.point_size;
+            output[out_byte_start..out_byte_start + buf.len()].copy_from_slice(&buf);
+
+            processed += window;
+        }
+
+        Ok(())
+    }
+
+    /// Windowed equivalent of `power_pairs`: confirms that a section's points
+    /// are consecutive powers of the same exponent without ever materializing
+    /// the whole vector, by reducing each window to a single `(s, s^x)` pair
+    /// via `merge_pairs` and accumulating those pairs across windows. The
+    /// caller (e.g. `verify_transform`'s windowed counterpart) compares the
+    /// returned pair against the corresponding G1/G2 generators with
+    /// `same_ratio`, exactly as `power_pairs` does for an in-memory vector.
+    pub fn windowed_power_pairs<G: AffineRepr>(
+        &self,
+        mmap: &Mmap,
+        section: &Section,
+        compress: Compress,
+    ) -> (G, G) {
+        use num_traits::identities::Zero;
+
+        let mut s = G::Group::zero();
+        let mut sx = G::Group::zero();
+
+        // Each window after the first overlaps the previous one by one point,
+        // so the pair straddling a window boundary is still checked.
+        let mut processed = 0;
+        while processed + 1 < section.len {
+            let window = self.batch_size.min(section.len - processed - 1) + 1;
+            let byte_start = section.offset + processed * section.point_size;
+            let byte_len = window * section.point_size;
+
+            let mut points = Vec::with_capacity(window);
+            let mut cursor = &mmap[byte_start..byte_start + byte_len];
+            for _ in 0..window {
+                points.push(
+                    G::deserialize_with_mode(&mut cursor, compress, Validate::No)
+                        .expect("unable to deserialize a window for verification"),
+                );
+            }
+
+            let (window_s, window_sx) = merge_pairs(&points[0..points.len() - 1], &points[1..]);
+            s += window_s;
+            sx += window_sx;
+
+            processed += window - 1;
+        }
+
+        (s.into_affine(), sx.into_affine())
+    }
+
+    /// Windowed counterpart to `crate::check_power_ratios`: confirms the four
+    /// `power_pairs` consistency checks an in-memory `Accumulator` gets, but
+    /// against a memory-mapped `response` a window at a time via
+    /// `windowed_power_pairs`, so a batched contribution can be checked
+    /// without ever deserializing the whole accumulator.
+    pub fn verify_power_ratios<P: Pairing>(
+        &self,
+        response: &Mmap,
+        params: &CeremonyParams<P>,
+        compression: UseCompression,
+    ) -> bool {
+        let (tau_g1, tau_g2, alpha_tau_g1, beta_tau_g1, _) = Self::sections::<P>(params, compression);
+        let compress: Compress = compression.into();
+
+        let read_point = |offset: usize, point_size: usize| -> &[u8] {
+            &response[offset..offset + point_size]
+        };
+        let tau_g1_0 = P::G1Affine::deserialize_with_mode(
+            read_point(tau_g1.offset, tau_g1.point_size),
+            compression.into(),
+            Validate::No,
+        )
+        .expect("unable to read tau_powers_g1[0] for verification");
+        let tau_g1_1 = P::G1Affine::deserialize_with_mode(
+            read_point(tau_g1.offset + tau_g1.point_size, tau_g1.point_size),
+            compression.into(),
+            Validate::No,
+        )
+        .expect("unable to read tau_powers_g1[1] for verification");
+        let tau_g2_0 = P::G2Affine::deserialize_with_mode(
+            read_point(tau_g2.offset, tau_g2.point_size),
+            compression.into(),
+            Validate::No,
+        )
+        .expect("unable to read tau_powers_g2[0] for verification");
+        let tau_g2_1 = P::G2Affine::deserialize_with_mode(
+            read_point(tau_g2.offset + tau_g2.point_size, tau_g2.point_size),
+            compression.into(),
+            Validate::No,
+        )
+        .expect("unable to read tau_powers_g2[1] for verification");
+
+        same_ratio::<P>(
+            self.windowed_power_pairs::<P::G1Affine>(response, &tau_g1, compress),
+            (tau_g2_0, tau_g2_1),
+        ) && same_ratio::<P>(
+            (tau_g1_0, tau_g1_1),
+            self.windowed_power_pairs::<P::G2Affine>(response, &tau_g2, compress),
+        ) && same_ratio::<P>(
+            self.windowed_power_pairs::<P::G1Affine>(response, &alpha_tau_g1, compress),
+            (tau_g2_0, tau_g2_1),
+        ) && same_ratio::<P>(
+            self.windowed_power_pairs::<P::G1Affine>(response, &beta_tau_g1, compress),
+            (tau_g2_0, tau_g2_1),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{blank_hash, keypair, Accumulator, CheckForCorrectness};
+    use ark_mnt6_753::MNT6_753;
+    use rand::thread_rng;
+    use std::fs::OpenOptions;
+
+    /// `contribute_batched`'s windowed path has to produce byte-for-byte the
+    /// same `response` as `main`'s in-memory `Accumulator::transform` +
+    /// `serialize`, since both are read by the same `verify`/`verify_transcript`.
+    /// This pinned a real bug: `Section::sections` omitted the 8-byte length
+    /// prefix `CanonicalSerialize` writes before each `Vec` field, so batched
+    /// offsets drifted from the actual on-disk layout.
+    #[test]
+    fn batched_transform_matches_in_memory_transform() {
+        let params = CeremonyParams::<MNT6_753>::new(2);
+        let batch_size = 3; // smaller than every section, to force multiple windows
+
+        let dir = std::env::temp_dir().join(format!(
+            "snark-mpc-setup-batched-accumulator-test-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).expect("unable to create test scratch directory");
+        let challenge_path = dir.join("challenge");
+        let output_path = dir.join("response");
+        let _ = std::fs::remove_file(&challenge_path);
+        let _ = std::fs::remove_file(&output_path);
+
+        // Lay out `./challenge` exactly as `main` does: a blank hash followed
+        // by a freshly initialized, uncompressed accumulator.
+        {
+            let mut writer = OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&challenge_path)
+                .expect("unable to create scratch challenge file");
+            use std::io::Write;
+            writer
+                .write_all(blank_hash().as_slice())
+                .expect("unable to write blank hash");
+            Accumulator::new(&params)
+                .serialize(&mut writer, UseCompression::No)
+                .expect("unable to write fresh accumulator");
+        }
+
+        let input_file = OpenOptions::new()
+            .read(true)
+            .open(&challenge_path)
+            .expect("unable to open scratch challenge file");
+        let input = unsafe { Mmap::map(&input_file) }.expect("unable to mmap scratch challenge");
+
+        let current_accumulator_hash = {
+            let mut hasher = Blake2b512::default();
+            hasher.update(&input[..]);
+            hasher.finalize()
+        };
+        let (pub_key, priv_key) =
+            keypair::<MNT6_753, _>(&mut thread_rng(), current_accumulator_hash.as_ref());
+
+        // The in-memory path: deserialize, transform, re-serialize.
+        let expected = {
+            let mut cursor = &input[64..];
+            let mut accumulator =
+                Accumulator::<MNT6_753>::deserialize(&mut cursor, UseCompression::No, CheckForCorrectness::No)
+                    .expect("unable to deserialize scratch accumulator");
+            accumulator.transform(&priv_key, &params);
+
+            let mut buf = current_accumulator_hash.as_slice().to_vec();
+            accumulator
+                .serialize(&mut buf, UseCompression::Yes)
+                .expect("unable to serialize transformed accumulator");
+            pub_key
+                .serialize_uncompressed(&mut buf)
+                .expect("unable to serialize public key");
+            buf
+        };
+
+        // The windowed path, through the same entry point `contribute_batched` uses.
+        let output_file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&output_path)
+            .expect("unable to create scratch response file");
+        output_file
+            .set_len(params.contribution_byte_size() as u64)
+            .expect("unable to size scratch response file");
+        let mut output =
+            unsafe { MmapMut::map_mut(&output_file) }.expect("unable to mmap scratch response");
+
+        let (hash, mut output_hasher) = BatchedAccumulator::new(batch_size)
+            .transform(&input, &mut output, &priv_key, &params, UseCompression::Yes)
+            .expect("unable to run batched transform");
+        assert_eq!(hash.as_slice(), current_accumulator_hash.as_slice());
+
+        let pubkey_offset = params.contribution_byte_size() - params.sizes.public_key_size();
+        let mut pubkey_bytes = Vec::new();
+        pub_key
+            .serialize_uncompressed(&mut pubkey_bytes)
+            .expect("unable to serialize public key");
+        output_hasher.update(&pubkey_bytes);
+        output[pubkey_offset..pubkey_offset + pubkey_bytes.len()].copy_from_slice(&pubkey_bytes);
+
+        assert_eq!(&output[..], expected.as_slice());
+
+        std::fs::remove_file(&challenge_path).ok();
+        std::fs::remove_file(&output_path).ok();
+    }
+
+    /// `BatchedAccumulator` is generic over `P: Pairing`, not hardcoded to
+    /// MNT6-753 -- this runs the same windowed transform and
+    /// `verify_power_ratios` check against BLS12-381 instead, with no
+    /// changes to this module, to prove that claim rather than just assert
+    /// it in a doc comment.
+    #[test]
+    fn transform_and_verify_power_ratios_work_on_a_second_curve() {
+        use ark_bls12_381::Bls12_381;
+
+        let params = CeremonyParams::<Bls12_381>::new(2);
+        let batch_size = 3; // smaller than every section, to force multiple windows
+
+        let dir = std::env::temp_dir().join(format!(
+            "snark-mpc-setup-batched-accumulator-bls12-381-test-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).expect("unable to create test scratch directory");
+        let challenge_path = dir.join("challenge");
+        let output_path = dir.join("response");
+        let _ = std::fs::remove_file(&challenge_path);
+        let _ = std::fs::remove_file(&output_path);
+
+        {
+            let mut writer = OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&challenge_path)
+                .expect("unable to create scratch challenge file");
+            use std::io::Write;
+            writer
+                .write_all(blank_hash().as_slice())
+                .expect("unable to write blank hash");
+            Accumulator::new(&params)
+                .serialize(&mut writer, UseCompression::No)
+                .expect("unable to write fresh accumulator");
+        }
+
+        let input_file = OpenOptions::new()
+            .read(true)
+            .open(&challenge_path)
+            .expect("unable to open scratch challenge file");
+        let input = unsafe { Mmap::map(&input_file) }.expect("unable to mmap scratch challenge");
+
+        let current_accumulator_hash = {
+            let mut hasher = Blake2b512::default();
+            hasher.update(&input[..]);
+            hasher.finalize()
+        };
+        let (_, priv_key) =
+            keypair::<Bls12_381, _>(&mut thread_rng(), current_accumulator_hash.as_ref());
+
+        let output_file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&output_path)
+            .expect("unable to create scratch response file");
+        output_file
+            .set_len(params.contribution_byte_size() as u64)
+            .expect("unable to size scratch response file");
+        let mut output =
+            unsafe { MmapMut::map_mut(&output_file) }.expect("unable to mmap scratch response");
+
+        BatchedAccumulator::new(batch_size)
+            .transform(&input, &mut output, &priv_key, &params, UseCompression::Yes)
+            .expect("unable to run batched transform");
+        drop(output);
+
+        let response = OpenOptions::new()
+            .read(true)
+            .open(&output_path)
+            .expect("unable to reopen scratch response file");
+        let response = unsafe { Mmap::map(&response) }.expect("unable to mmap scratch response");
+
+        assert!(BatchedAccumulator::new(batch_size).verify_power_ratios(
+            &response,
+            &params,
+            UseCompression::Yes
+        ));
+
+        std::fs::remove_file(&challenge_path).ok();
+        std::fs::remove_file(&output_path).ok();
+    }
+}
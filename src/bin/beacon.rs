@@ -0,0 +1,185 @@
+//! Closes a ceremony with a public randomness beacon instead of private
+//! entropy, so the final contribution can be verified by anyone re-running
+//! this binary with the same public inputs, rather than trusted on faith.
+//!
+//! Takes a hex-encoded `beacon_hash` and an exponent `num_iterations_exp` in
+//! `[10, 63]`, then derives the seed for `keypair` by applying SHA256
+//! `2^num_iterations_exp` times: `h = SHA256(beacon_hash)`, then
+//! `h = SHA256(h)` that many times more. Squaring the iteration count with
+//! the exponent is a verifiable delay -- nobody, including whoever picked
+//! `beacon_hash`, can grind it to bias the outcome, since computing the
+//! final digest can't be parallelized or skipped ahead.
+
+use ark_mnt6_753::MNT6_753;
+use ark_serialize::CanonicalSerialize;
+use rand::SeedableRng;
+use rand_chacha::ChaChaRng;
+use sha2::{Digest, Sha256};
+use std::fs::OpenOptions;
+use std::io::{BufReader, BufWriter, Read, Write};
+
+use snark_mpc_setup::{
+    keypair, Accumulator, CeremonyParams, CheckForCorrectness, HashReader, HashWriter,
+    UseCompression,
+};
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() != 3 {
+        eprintln!(
+            "Usage: {} <beacon_hash_hex> <num_iterations_exp>",
+            args.first().map(String::as_str).unwrap_or("beacon")
+        );
+        std::process::exit(1);
+    }
+
+    let beacon_hash = hex_decode(&args[1]).expect("beacon_hash must be valid hex");
+    let num_iterations_exp: u32 = args[2]
+        .parse()
+        .expect("num_iterations_exp must be an integer");
+    assert!(
+        (10..=63).contains(&num_iterations_exp),
+        "num_iterations_exp must be in [10, 63]"
+    );
+
+    // `./challenge` is whatever the last contributor's `response` became, so
+    // this has to run the same `2^5`-gate MNT6-753 ceremony `main` started,
+    // or the size check below will reject it.
+    let params = CeremonyParams::<MNT6_753>::new(5);
+
+    println!("Running the randomness beacon's verifiable delay, this could take a while...");
+    let mut h: [u8; 32] = Sha256::digest(&beacon_hash).into();
+    let num_iterations = 1u64 << num_iterations_exp;
+    for _ in 0..num_iterations {
+        h = Sha256::digest(h).into();
+    }
+    let mut rng = ChaChaRng::from_seed(h);
+
+    let reader = OpenOptions::new()
+        .read(true)
+        .open("challenge")
+        .expect("unable to open `./challenge` in this directory");
+
+    {
+        let metadata = reader
+            .metadata()
+            .expect("unable to get filesystem metadata for `./challenge`");
+        if metadata.len() != (params.accumulator_byte_size_with_hash() as u64) {
+            panic!(
+                "The size of `./challenge` should be {}, but it's {}, so something isn't right.",
+                params.accumulator_byte_size_with_hash(),
+                metadata.len()
+            );
+        }
+    }
+
+    let reader = BufReader::new(reader);
+    let mut reader = HashReader::new(reader);
+
+    let writer = OpenOptions::new()
+        .read(false)
+        .write(true)
+        .create_new(true)
+        .open("response")
+        .expect("unable to create `./response` in this directory");
+
+    let writer = BufWriter::new(writer);
+    let mut writer = HashWriter::new(writer);
+
+    // Read the BLAKE2b hash of the previous contribution; unneeded here, but
+    // it's important for the hash chain.
+    let mut tmp = [0; 64];
+    reader
+        .read_exact(&mut tmp)
+        .expect("unable to read BLAKE2b hash of previous contribution");
+
+    let mut current_accumulator: Accumulator<MNT6_753> =
+        Accumulator::deserialize(&mut reader, UseCompression::No, CheckForCorrectness::No)
+            .expect("unable to read uncompressed accumulator");
+
+    let current_accumulator_hash = reader.into_hash();
+
+    // Derive the keypair exactly as the interactive contributor flow does,
+    // but from the beacon-seeded RNG instead of user/system entropy.
+    let (pub_key, priv_key) = keypair::<MNT6_753, _>(&mut rng, current_accumulator_hash.as_ref());
+
+    println!("Computing, this could take a while...");
+    current_accumulator.transform(&priv_key, &params);
+    println!("Writing the beacon's contribution to `./response`...");
+
+    writer
+        .write_all(current_accumulator_hash.as_ref())
+        .expect("unable to write BLAKE2b hash of input accumulator");
+
+    current_accumulator
+        .serialize(&mut writer, UseCompression::Yes)
+        .expect("unable to write transformed accumulator");
+
+    pub_key
+        .serialize_uncompressed(&mut writer)
+        .expect("unable to write public key");
+
+    let contribution_hash = writer.into_hash();
+
+    print!(
+        "Done!\n\n\
+              The beacon's contribution has been written to `./response`\n\n\
+              The BLAKE2b hash of `./response` is:\n"
+    );
+    for line in contribution_hash.as_slice().chunks(16) {
+        print!("\t");
+        for section in line.chunks(4) {
+            for b in section {
+                print!("{:02x}", b);
+            }
+            print!(" ");
+        }
+        println!();
+    }
+    println!("\n");
+}
+
+/// Decodes a hex string into bytes, rejecting odd-length or non-hex input.
+/// Works over `s`'s raw bytes rather than slicing the `&str` itself, so a
+/// multi-byte UTF-8 input can't land a slice off a char boundary and panic.
+fn hex_decode(s: &str) -> Result<Vec<u8>, String> {
+    let bytes = s.as_bytes();
+    if !bytes.len().is_multiple_of(2) {
+        return Err(format!("hex string has odd length {}", bytes.len()));
+    }
+    bytes
+        .chunks(2)
+        .map(|pair| {
+            let pair = std::str::from_utf8(pair).map_err(|e| e.to_string())?;
+            u8::from_str_radix(pair, 16).map_err(|e| e.to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_hex_round_trips() {
+        assert_eq!(hex_decode("").unwrap(), Vec::<u8>::new());
+        assert_eq!(hex_decode("00ff10").unwrap(), vec![0x00, 0xff, 0x10]);
+        assert_eq!(hex_decode("DEADBEEF").unwrap(), vec![0xde, 0xad, 0xbe, 0xef]);
+    }
+
+    /// The bug `de4aef9` fixed: an odd-length input used to panic on the
+    /// trailing unpaired nibble instead of returning `Err`.
+    #[test]
+    fn odd_length_input_is_rejected_not_panicked() {
+        assert!(hex_decode("abc").is_err());
+        assert!(hex_decode("a").is_err());
+    }
+
+    /// The bug `2f8afc0` fixed: non-ASCII input used to panic by slicing a
+    /// multi-byte UTF-8 char off a byte boundary instead of returning `Err`.
+    #[test]
+    fn non_hex_input_is_rejected_not_panicked() {
+        assert!(hex_decode("zz").is_err());
+        assert!(hex_decode("ab€f").is_err());
+    }
+}
@@ -0,0 +1,128 @@
+//! Memory-bounded counterpart to `main`'s interactive contribution flow.
+//!
+//! `main` loads the whole challenge into an `Accumulator` before calling
+//! `Accumulator::transform`, which is infeasible once `tau_powers_g1_length`
+//! is in the tens of millions. This binary instead memory-maps `./challenge`
+//! and `./response` and runs [`transform_batched`], which processes the
+//! tau/alpha/beta vectors a fixed-size `batch_size` window at a time and
+//! writes the compressed response straight through, keeping peak memory
+//! bounded by `batch_size` rather than by the ceremony's size.
+
+use ark_mnt6_753::MNT6_753;
+use ark_serialize::CanonicalSerialize;
+use blake2::{Blake2b512, Digest};
+use memmap2::{Mmap, MmapMut};
+use rand::thread_rng;
+use std::fs::OpenOptions;
+
+use snark_mpc_setup::{keypair, transform_batched, verify_batched_power_ratios, CeremonyParams, UseCompression};
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() != 2 {
+        eprintln!(
+            "Usage: {} <batch_size>",
+            args.first().map(String::as_str).unwrap_or("contribute_batched")
+        );
+        std::process::exit(1);
+    }
+    let batch_size: usize = args[1].parse().expect("batch_size must be a positive integer");
+
+    // `./challenge` here is the same tiny `2^5`-gate MNT6-753 ceremony
+    // `main` starts -- `params` is only used to size the mmap and section
+    // offsets `transform_batched` windows over, so it has to agree.
+    let params = CeremonyParams::<MNT6_753>::new(5);
+
+    let input_file = OpenOptions::new()
+        .read(true)
+        .open("challenge")
+        .expect("unable to open `./challenge` in this directory");
+
+    {
+        let metadata = input_file
+            .metadata()
+            .expect("unable to get filesystem metadata for `./challenge`");
+        if metadata.len() != (params.accumulator_byte_size_with_hash() as u64) {
+            panic!(
+                "The size of `./challenge` should be {}, but it's {}, so something isn't right.",
+                params.accumulator_byte_size_with_hash(),
+                metadata.len()
+            );
+        }
+    }
+
+    let input = unsafe { Mmap::map(&input_file) }.expect("unable to mmap `./challenge`");
+
+    let output_file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create_new(true)
+        .open("response")
+        .expect("unable to create `./response` in this directory");
+    output_file
+        .set_len(params.contribution_byte_size() as u64)
+        .expect("unable to size `./response`");
+    let mut output = unsafe { MmapMut::map_mut(&output_file) }.expect("unable to mmap `./response`");
+
+    // The hash of the whole challenge is what `keypair` (and, at
+    // verification time, `verify_transform`) are run against; `transform_batched`
+    // recomputes and returns the same value so we can cross-check it below.
+    let current_accumulator_hash = {
+        let mut hasher = Blake2b512::default();
+        hasher.update(&input[..]);
+        hasher.finalize()
+    };
+
+    let rng = &mut thread_rng();
+    let (pub_key, priv_key) = keypair::<MNT6_753, _>(rng, current_accumulator_hash.as_ref());
+
+    println!("Computing in batches of {batch_size}, this could take a while...");
+    let (hash, mut output_hasher) = transform_batched(
+        &input,
+        &mut output,
+        &priv_key,
+        &params,
+        batch_size,
+        UseCompression::Yes,
+    )
+    .expect("unable to run batched transform");
+    assert_eq!(hash.as_slice(), current_accumulator_hash.as_slice());
+    println!("Writing your contribution to `./response`...");
+
+    let pubkey_offset = params.contribution_byte_size() - params.sizes.public_key_size();
+    let mut pubkey_bytes = Vec::new();
+    pub_key
+        .serialize_uncompressed(&mut pubkey_bytes)
+        .expect("unable to serialize public key");
+    output_hasher.update(&pubkey_bytes);
+    output[pubkey_offset..pubkey_offset + pubkey_bytes.len()].copy_from_slice(&pubkey_bytes);
+
+    output.flush().expect("unable to flush `./response` to disk");
+
+    // Sanity-check our own output the same windowed way a verifier would,
+    // before telling the contributor it's safe to hand `./response` off.
+    let response = unsafe { Mmap::map(&output_file) }.expect("unable to mmap `./response` for self-check");
+    assert!(
+        verify_batched_power_ratios(&response, &params, batch_size, UseCompression::Yes),
+        "our own contribution to `./response` failed its power-ratios self-check"
+    );
+
+    let contribution_hash = output_hasher.finalize();
+
+    print!(
+        "Done!\n\n\
+              Your contribution has been written to `./response`\n\n\
+              The BLAKE2b hash of `./response` is:\n"
+    );
+    for line in contribution_hash.as_slice().chunks(16) {
+        print!("\t");
+        for section in line.chunks(4) {
+            for b in section {
+                print!("{:02x}", b);
+            }
+            print!(" ");
+        }
+        println!();
+    }
+    println!("\n");
+}
@@ -0,0 +1,45 @@
+//! Frame-compresses an already-finished `challenge`/`response` file for
+//! bandwidth-constrained transport, via `framed::FrameWriter`. Doesn't touch
+//! `main`/`beacon`/`contribute_batched`'s own on-disk format -- see
+//! `framed`'s module doc for why weaving framing into those is a bigger
+//! change. `verify` reads the `.framed` output this produces directly.
+
+use snark_mpc_setup::framed::{FrameCodec, FrameWriter};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() != 4 {
+        eprintln!(
+            "Usage: {} <input file> <output .framed file> <none|zstd|snappy>",
+            args.first().map(String::as_str).unwrap_or("pack")
+        );
+        std::process::exit(1);
+    }
+
+    let codec = match args[3].as_str() {
+        "none" => FrameCodec::None,
+        "zstd" => FrameCodec::Zstd,
+        "snappy" => FrameCodec::Snappy,
+        other => {
+            eprintln!("unknown codec `{other}`, expected none|zstd|snappy");
+            std::process::exit(1);
+        }
+    };
+
+    let input = File::open(&args[1]).unwrap_or_else(|e| panic!("unable to open `{}`: {e}", args[1]));
+    let output =
+        File::create(&args[2]).unwrap_or_else(|e| panic!("unable to create `{}`: {e}", args[2]));
+
+    let mut reader = BufReader::new(input);
+    let mut writer = FrameWriter::new(BufWriter::new(output), codec);
+
+    io::copy(&mut reader, &mut writer)
+        .unwrap_or_else(|e| panic!("unable to frame-compress `{}`: {e}", args[1]));
+    writer
+        .flush()
+        .unwrap_or_else(|e| panic!("unable to flush `{}`: {e}", args[2]));
+
+    println!("Wrote frame-compressed `{}` to `{}`", args[1], args[2]);
+}
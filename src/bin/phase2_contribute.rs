@@ -0,0 +1,191 @@
+//! Phase 2: derives Groth16 parameters for a small built-in demonstration
+//! circuit from phase 1's finished transcript, then runs one phase 2
+//! contribution over them -- the phase 2 analogue of `main`'s phase 1
+//! contribution.
+//!
+//! Reads `./response` -- the last phase 1 contribution in the ceremony,
+//! untrusted like `verify` treats it -- derives [`phase2::Phase2Parameters`]
+//! for [`IdentityCircuit`] via [`phase2::generate_parameters`], contributes
+//! fresh entropy to `delta` via [`phase2::contribute`], self-checks the
+//! result with [`phase2::phase2_verify`] before trusting it, and writes the
+//! transcript this contribution was chained to plus the resulting parameters
+//! and public key to `./phase2_response`.
+
+use ark_mnt6_753::{Fr, MNT6_753};
+use ark_serialize::CanonicalSerialize;
+use ark_std::One;
+use blake2::{Blake2b512, Digest};
+use std::fs::OpenOptions;
+use std::io::{self, BufReader, Read, Write};
+
+use snark_mpc_setup::phase2::{self, Circuit};
+use snark_mpc_setup::{Accumulator, CeremonyParams, CheckForCorrectness, HashReader, UseCompression};
+
+/// A minimal `size`-constraint, `size`-variable circuit: row `i` asserts
+/// `variable[i] * 0 = 0`. Not a circuit anyone would prove anything useful
+/// with -- just enough real structure (a power-of-two domain, dense A/B/C
+/// rows) to exercise `generate_parameters`'s IFFT and query-combination logic
+/// against a real ceremony accumulator end to end.
+struct IdentityCircuit {
+    a: Vec<Vec<Fr>>,
+    b: Vec<Vec<Fr>>,
+    c: Vec<Vec<Fr>>,
+}
+
+impl IdentityCircuit {
+    fn new(size: usize) -> Self {
+        assert!(size.is_power_of_two(), "size must be a power of two");
+        let mut a = vec![vec![Fr::from(0u64); size]; size];
+        for (i, row) in a.iter_mut().enumerate() {
+            row[i] = Fr::one();
+        }
+        let b = vec![vec![Fr::from(0u64); size]; size];
+        let c = vec![vec![Fr::from(0u64); size]; size];
+        IdentityCircuit { a, b, c }
+    }
+}
+
+impl Circuit<Fr> for IdentityCircuit {
+    fn num_variables(&self) -> usize {
+        self.a[0].len()
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.a.len()
+    }
+
+    fn a(&self) -> &[Vec<Fr>] {
+        &self.a
+    }
+
+    fn b(&self) -> &[Vec<Fr>] {
+        &self.b
+    }
+
+    fn c(&self) -> &[Vec<Fr>] {
+        &self.c
+    }
+}
+
+fn main() {
+    // `contribution_byte_size` below size-checks `./response`, and
+    // `tau_powers_length` bounds how many tau powers `generate_parameters`
+    // reads out of it, so `params` has to describe whatever phase 1
+    // ceremony actually produced `./response` -- here, `main`'s tiny
+    // `2^5`-gate MNT6-753 one.
+    let params = CeremonyParams::<MNT6_753>::new(5);
+    let circuit = IdentityCircuit::new(8);
+
+    let reader = OpenOptions::new()
+        .read(true)
+        .open("response")
+        .expect("unable to open `./response` in this directory");
+
+    let metadata = reader
+        .metadata()
+        .expect("unable to get filesystem metadata for `./response`");
+    if metadata.len() != (params.contribution_byte_size() as u64) {
+        panic!(
+            "The size of `./response` should be {}, but it's {}, so something isn't right.",
+            params.contribution_byte_size(),
+            metadata.len()
+        );
+    }
+
+    let reader = BufReader::new(reader);
+    let mut reader = HashReader::new(reader);
+
+    let mut previous_hash = [0u8; 64];
+    reader
+        .read_exact(&mut previous_hash)
+        .expect("unable to read BLAKE2b hash header of `./response`");
+
+    // Untrusted, like `verify` treats every response: a malicious phase 1
+    // contributor's output must still fail to produce usable phase 2
+    // parameters.
+    let accumulator: Accumulator<MNT6_753> =
+        Accumulator::deserialize(&mut reader, UseCompression::Yes, CheckForCorrectness::Yes)
+            .expect("unable to read accumulator from `./response`");
+
+    // Consume (but don't need) the public key, then chain phase 2's
+    // transcript to the BLAKE2b hash of the whole `./response` file, the
+    // same way phase 1 chains each contribution to the hash of the one
+    // before it.
+    io::copy(&mut reader, &mut io::sink()).expect("unable to read public key from `./response`");
+    let transcript: [u8; 64] = reader.into_hash().as_slice().try_into().unwrap();
+
+    println!("Deriving phase 2 parameters from `./response`, this could take a while...");
+    let before = phase2::generate_parameters(&accumulator, &circuit, &params);
+
+    let rng = &mut entropy_rng();
+    let mut after = before.clone();
+    let public_key = phase2::contribute(&mut after, rng, &transcript);
+
+    assert!(
+        phase2::phase2_verify(&before, &after, &public_key, &transcript),
+        "our own phase 2 contribution failed its own verification"
+    );
+
+    let writer = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open("phase2_response")
+        .expect("unable to create `./phase2_response` in this directory");
+    let mut writer = io::BufWriter::new(writer);
+
+    writer
+        .write_all(&transcript)
+        .expect("unable to write transcript to `./phase2_response`");
+    after
+        .serialize_uncompressed(&mut writer)
+        .expect("unable to write phase 2 parameters to `./phase2_response`");
+    public_key
+        .serialize_uncompressed(&mut writer)
+        .expect("unable to write phase 2 public key to `./phase2_response`");
+    writer
+        .flush()
+        .expect("unable to flush `./phase2_response` to disk");
+
+    print!(
+        "Done!\n\n\
+              Your phase 2 contribution has been written to `./phase2_response`\n\n\
+              The BLAKE2b hash of the resulting parameters is:\n"
+    );
+    for line in phase2::transcript_hash(&after).chunks(16) {
+        print!("\t");
+        for section in line.chunks(4) {
+            for b in section {
+                print!("{:02x}", b);
+            }
+            print!(" ");
+        }
+        println!();
+    }
+    println!("\n");
+}
+
+/// Gathers entropy from the OS and the user, exactly as `main`'s phase 1
+/// contribution does, to seed the RNG `phase2::contribute` draws its fresh
+/// `delta` from.
+fn entropy_rng() -> rand_chacha::ChaChaRng {
+    use rand::{rngs::OsRng, Rng, SeedableRng};
+
+    let mut system_rng = OsRng;
+    let mut h = Blake2b512::default();
+    for _ in 0..1024 {
+        let r: u8 = system_rng.gen();
+        h.update([r]);
+    }
+
+    let mut user_input = String::new();
+    println!("Type some random text and press [ENTER] to provide additional entropy...");
+    io::stdin()
+        .read_line(&mut user_input)
+        .expect("expected to read some random text from the user");
+    h.update(user_input.as_bytes());
+
+    let digest = h.finalize();
+    let mut seed = [0; 32];
+    seed.copy_from_slice(&digest[..32]);
+    rand_chacha::ChaChaRng::from_seed(seed)
+}
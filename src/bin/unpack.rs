@@ -0,0 +1,33 @@
+//! Reverses `pack`: streams a `.framed` file back into its original plain
+//! bytes via `framed::FrameReader`, one frame at a time rather than
+//! buffering the whole (possibly much larger, decompressed) file in memory.
+
+use snark_mpc_setup::framed::FrameReader;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() != 3 {
+        eprintln!(
+            "Usage: {} <input .framed file> <output file>",
+            args.first().map(String::as_str).unwrap_or("unpack")
+        );
+        std::process::exit(1);
+    }
+
+    let input = File::open(&args[1]).unwrap_or_else(|e| panic!("unable to open `{}`: {e}", args[1]));
+    let output =
+        File::create(&args[2]).unwrap_or_else(|e| panic!("unable to create `{}`: {e}", args[2]));
+
+    let mut reader = FrameReader::new(BufReader::new(input));
+    let mut writer = BufWriter::new(output);
+
+    io::copy(&mut reader, &mut writer)
+        .unwrap_or_else(|e| panic!("unable to un-frame `{}`: {e}", args[1]));
+    writer
+        .flush()
+        .unwrap_or_else(|e| panic!("unable to flush `{}`: {e}", args[2]));
+
+    println!("Wrote plain `{}` to `{}`", args[2], args[1]);
+}
@@ -0,0 +1,189 @@
+//! Verifies a whole ceremony transcript end to end: given the initial
+//! `./challenge` and the ordered sequence of `response` files it was
+//! transformed into, confirms each `response`'s "hash of previous
+//! contribution" really is the BLAKE2b hash of the file before it, and that
+//! each transformation carries a valid proof of knowledge of the new
+//! tau/alpha/beta, via [`verify_transcript`]. Fails loudly -- prints which
+//! contribution broke the chain and exits nonzero -- on the first link that
+//! doesn't check out, and prints every accepted response's own hash along
+//! the way, so a contributor can match it against what their own run of
+//! `main`/`beacon` printed.
+//!
+//! Any input path ending in `.framed` (as produced by `pack`) is read
+//! through `framed::FrameReader` instead of straight off disk -- streaming
+//! the decompression, since `Accumulator`/`PublicKey`'s derived
+//! `CanonicalDeserialize` reads its own length-prefixed data and never needs
+//! the whole file up front. A `.framed` input skips the plain-file exact-size
+//! sanity check below, since a compressed file's on-disk size isn't
+//! comparable to the uncompressed one; malformed input still fails to
+//! deserialize, just without that earlier, friendlier error.
+
+use ark_mnt6_753::MNT6_753;
+use generic_array::GenericArray;
+use std::fs::OpenOptions;
+use std::io::{BufReader, Read};
+use typenum::consts::U64;
+
+use snark_mpc_setup::framed::FrameReader;
+use snark_mpc_setup::{
+    verify_transcript, Accumulator, CeremonyParams, CheckForCorrectness, Contribution,
+    HashReader, PublicKey, UseCompression,
+};
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() < 3 {
+        eprintln!(
+            "Usage: {} <initial challenge> <response> [response ...]",
+            args.first().map(String::as_str).unwrap_or("verify")
+        );
+        std::process::exit(1);
+    }
+
+    // `read_challenge`/`read_response` below size-check every file against
+    // `params`, so this has to match whatever ceremony actually produced the
+    // challenge/responses passed on the command line -- here, `main`'s tiny
+    // `2^5`-gate MNT6-753 ceremony.
+    let params = CeremonyParams::<MNT6_753>::new(5);
+
+    let (initial_hash, initial) = read_challenge(&args[1], &params);
+
+    let mut contributions = Vec::with_capacity(args.len() - 2);
+    for path in &args[2..] {
+        contributions.push(read_response(path, &params));
+    }
+
+    let results = verify_transcript(&initial, initial_hash, &contributions);
+
+    for (i, (result, contribution)) in results.iter().zip(contributions.iter()).enumerate() {
+        println!(
+            "{}: {}",
+            args[i + 2],
+            match result {
+                Ok(()) => "OK".to_string(),
+                Err(e) => format!("FAILED ({e:?})"),
+            }
+        );
+        print!("\tBLAKE2b hash of this response: ");
+        for b in contribution.response_hash.as_slice() {
+            print!("{:02x}", b);
+        }
+        println!();
+
+        if let Err(e) = result {
+            eprintln!(
+                "\nTranscript verification failed at contribution {} ({}): {:?}",
+                i + 1,
+                args[i + 2],
+                e
+            );
+            std::process::exit(1);
+        }
+    }
+
+    println!("\nThe whole transcript verified successfully.");
+}
+
+/// Reads the initial, always-uncompressed accumulator out of `path`,
+/// trusting it (as `main` does for a freshly written `./challenge`) rather
+/// than validating every point, since it has no prior contribution that
+/// could have tampered with it. Also returns the 64-byte header `path`
+/// embeds ahead of the accumulator -- `verify_transcript` needs it to
+/// recompute the hash the first contribution's `keypair` was run against,
+/// exactly as `HashReader` would have hashed the whole file.
+fn read_challenge(
+    path: &str,
+    params: &CeremonyParams<MNT6_753>,
+) -> (GenericArray<u8, U64>, Accumulator<MNT6_753>) {
+    let file = OpenOptions::new()
+        .read(true)
+        .open(path)
+        .unwrap_or_else(|e| panic!("unable to open `{path}`: {e}"));
+
+    if !path.ends_with(".framed") {
+        let metadata = file
+            .metadata()
+            .unwrap_or_else(|e| panic!("unable to get filesystem metadata for `{path}`: {e}"));
+        if metadata.len() != (params.accumulator_byte_size_with_hash() as u64) {
+            panic!(
+                "The size of `{path}` should be {}, but it's {}, so something isn't right.",
+                params.accumulator_byte_size_with_hash(),
+                metadata.len()
+            );
+        }
+    }
+
+    let mut reader = HashReader::new(open_framed_aware(path, file));
+    let mut header = [0u8; 64];
+    reader
+        .read_exact(&mut header)
+        .unwrap_or_else(|e| panic!("unable to read BLAKE2b hash header of `{path}`: {e}"));
+
+    let accumulator =
+        Accumulator::deserialize(&mut reader, UseCompression::No, CheckForCorrectness::No)
+            .unwrap_or_else(|e| panic!("unable to read accumulator from `{path}`: {e}"));
+
+    (GenericArray::clone_from_slice(&header), accumulator)
+}
+
+/// Reads a `response` file -- a 64-byte "hash of previous contribution",
+/// then the transformed accumulator (compressed), then the contributor's
+/// `PublicKey` (uncompressed) -- into a [`Contribution`], including this
+/// file's own BLAKE2b hash (`response_hash`) so it can be printed back for
+/// the contributor to check against, and so `verify_transcript` can carry it
+/// forward as the next contribution's challenge header.
+fn read_response(path: &str, params: &CeremonyParams<MNT6_753>) -> Contribution<MNT6_753> {
+    let file = OpenOptions::new()
+        .read(true)
+        .open(path)
+        .unwrap_or_else(|e| panic!("unable to open `{path}`: {e}"));
+
+    if !path.ends_with(".framed") {
+        let metadata = file
+            .metadata()
+            .unwrap_or_else(|e| panic!("unable to get filesystem metadata for `{path}`: {e}"));
+        if metadata.len() != (params.contribution_byte_size() as u64) {
+            panic!(
+                "The size of `{path}` should be {}, but it's {}, so something isn't right.",
+                params.contribution_byte_size(),
+                metadata.len()
+            );
+        }
+    }
+
+    let mut reader = HashReader::new(open_framed_aware(path, file));
+
+    let mut previous_hash = [0u8; 64];
+    reader
+        .read_exact(&mut previous_hash)
+        .unwrap_or_else(|e| panic!("unable to read BLAKE2b hash header of `{path}`: {e}"));
+
+    // Unlike `./challenge`, a `response` comes from an untrusted participant,
+    // so every point gets validated rather than taken on faith.
+    let accumulator =
+        Accumulator::deserialize(&mut reader, UseCompression::Yes, CheckForCorrectness::Yes)
+            .unwrap_or_else(|e| panic!("unable to read accumulator from `{path}`: {e}"));
+    let public_key = PublicKey::<MNT6_753>::deserialize(&mut reader, CheckForCorrectness::Yes)
+        .unwrap_or_else(|e| panic!("unable to read public key from `{path}`: {e}"));
+
+    let response_hash = reader.into_hash();
+
+    Contribution {
+        accumulator,
+        public_key,
+        previous_hash: GenericArray::clone_from_slice(&previous_hash),
+        response_hash,
+    }
+}
+
+/// Wraps `file` in `FrameReader` when `path` ends in `.framed` (as produced
+/// by `pack`), otherwise just buffers it -- so every caller downstream reads
+/// through a single `Read` either way, transparently decompressing a framed
+/// transcript one frame at a time instead of requiring it be unpacked first.
+fn open_framed_aware(path: &str, file: std::fs::File) -> Box<dyn Read> {
+    if path.ends_with(".framed") {
+        Box::new(FrameReader::new(BufReader::new(file)))
+    } else {
+        Box::new(BufReader::new(file))
+    }
+}
@@ -0,0 +1,180 @@
+//! Commit-reveal for contributor public keys.
+//!
+//! `main`'s interactive flow (and `beacon`'s) writes a participant's
+//! `PublicKey` straight into `response` alongside the transformed
+//! accumulator, chosen after the participant has already seen every prior
+//! contribution's hash. That leaves a window for an adaptive participant to
+//! pick `tau`/`alpha`/`beta` in response to what's already in the transcript
+//! -- a rogue-key attack `same_ratio`'s proof-of-knowledge checks don't
+//! catch, since each one only proves the *new* key is well-formed, not that
+//! it was chosen independently of anyone else's.
+//!
+//! `Coordinator` closes that window by splitting key publication into two
+//! rounds: in round one every participant submits only [`commit`]'s 64-byte
+//! BLAKE2b digest of their `PublicKey`; only once every participant has
+//! committed does round two accept the real key, which [`Coordinator::reveal`]
+//! checks against the matching commitment before it's let into the
+//! transcript.
+
+use crate::PublicKey;
+use ark_ec::pairing::Pairing;
+use ark_serialize::CanonicalSerialize;
+use blake2::{Blake2b512, Digest};
+use generic_array::GenericArray;
+use std::marker::PhantomData;
+use typenum::consts::U64;
+
+/// A 64-byte BLAKE2b commitment to a participant's serialized `PublicKey`,
+/// published in round one, before anyone's real key is revealed.
+pub fn commit<P: Pairing>(key: &PublicKey<P>) -> GenericArray<u8, U64> {
+    let mut hasher = Blake2b512::default();
+    key.serialize_uncompressed(&mut hasher)
+        .expect("unable to serialize public key for commitment");
+    hasher.finalize()
+}
+
+/// Why a participant's round-one commit or round-two reveal was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevealError {
+    /// `participant` is outside `0..num_participants` for this `Coordinator`.
+    ParticipantOutOfRange,
+    /// This participant never published a commitment in round one.
+    NoCommitment,
+    /// Not every participant has committed yet, so no reveal can be
+    /// accepted -- accepting one early would let a still-uncommitted
+    /// participant choose their key after seeing it, exactly what
+    /// commit-reveal exists to prevent.
+    StillCollectingCommitments,
+    /// This participant already revealed; reveals aren't resubmittable.
+    AlreadyRevealed,
+    /// The revealed key's commitment doesn't match what was published in
+    /// round one.
+    CommitmentMismatch,
+}
+
+/// Collects every participant's round-one commitment before accepting any
+/// round-two reveal, indexed by participant number `0..num_participants`.
+pub struct Coordinator<P: Pairing> {
+    commitments: Vec<Option<GenericArray<u8, U64>>>,
+    revealed: Vec<bool>,
+    _pairing: PhantomData<P>,
+}
+
+impl<P: Pairing> Coordinator<P> {
+    /// Opens round one for `num_participants` participants.
+    pub fn new(num_participants: usize) -> Self {
+        Coordinator {
+            commitments: vec![None; num_participants],
+            revealed: vec![false; num_participants],
+            _pairing: PhantomData,
+        }
+    }
+
+    /// Records `participant`'s round-one commitment, overwriting any earlier
+    /// one from the same participant, since nothing has been revealed
+    /// against it yet.
+    pub fn commit(
+        &mut self,
+        participant: usize,
+        commitment: GenericArray<u8, U64>,
+    ) -> Result<(), RevealError> {
+        let slot = self
+            .commitments
+            .get_mut(participant)
+            .ok_or(RevealError::ParticipantOutOfRange)?;
+        *slot = Some(commitment);
+        Ok(())
+    }
+
+    /// Whether every participant has published a round-one commitment, so
+    /// round two can begin.
+    pub fn all_committed(&self) -> bool {
+        self.commitments.iter().all(Option::is_some)
+    }
+
+    /// Accepts `participant`'s round-two reveal of their real `PublicKey`,
+    /// rejecting it unless it matches the commitment they published in
+    /// round one.
+    pub fn reveal(&mut self, participant: usize, key: &PublicKey<P>) -> Result<(), RevealError> {
+        if !self.all_committed() {
+            return Err(RevealError::StillCollectingCommitments);
+        }
+        let revealed = self
+            .revealed
+            .get_mut(participant)
+            .ok_or(RevealError::ParticipantOutOfRange)?;
+        if *revealed {
+            return Err(RevealError::AlreadyRevealed);
+        }
+        let expected = self.commitments[participant]
+            .as_ref()
+            .ok_or(RevealError::NoCommitment)?;
+        if commit::<P>(key) != *expected {
+            return Err(RevealError::CommitmentMismatch);
+        }
+
+        *revealed = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keypair;
+    use ark_mnt6_753::MNT6_753;
+    use rand::thread_rng;
+
+    /// Two participants commit, then reveal in a different order than they
+    /// committed; both reveals should be accepted once both have committed.
+    #[test]
+    fn commit_then_reveal_round_trip() {
+        let (key_a, _) = keypair::<MNT6_753, _>(&mut thread_rng(), &[0u8; 64]);
+        let (key_b, _) = keypair::<MNT6_753, _>(&mut thread_rng(), &[1u8; 64]);
+
+        let mut coordinator = Coordinator::<MNT6_753>::new(2);
+        coordinator.commit(0, commit(&key_a)).unwrap();
+        assert!(!coordinator.all_committed());
+        coordinator.commit(1, commit(&key_b)).unwrap();
+        assert!(coordinator.all_committed());
+
+        coordinator.reveal(1, &key_b).unwrap();
+        coordinator.reveal(0, &key_a).unwrap();
+    }
+
+    /// A reveal whose key doesn't hash to the published commitment is
+    /// rejected, instead of silently accepted as someone else's key.
+    #[test]
+    fn reveal_rejects_key_not_matching_commitment() {
+        let (key_a, _) = keypair::<MNT6_753, _>(&mut thread_rng(), &[0u8; 64]);
+        let (key_b, _) = keypair::<MNT6_753, _>(&mut thread_rng(), &[1u8; 64]);
+
+        let mut coordinator = Coordinator::<MNT6_753>::new(1);
+        coordinator.commit(0, commit(&key_a)).unwrap();
+
+        assert_eq!(
+            coordinator.reveal(0, &key_b),
+            Err(RevealError::CommitmentMismatch)
+        );
+    }
+
+    /// `commit`/`reveal` on an out-of-range participant index must return
+    /// `ParticipantOutOfRange` instead of panicking on the underlying `Vec`
+    /// index.
+    #[test]
+    fn out_of_range_participant_is_rejected_not_panicked() {
+        let (key_a, _) = keypair::<MNT6_753, _>(&mut thread_rng(), &[0u8; 64]);
+
+        let mut coordinator = Coordinator::<MNT6_753>::new(1);
+        assert_eq!(
+            coordinator.commit(5, commit(&key_a)),
+            Err(RevealError::ParticipantOutOfRange)
+        );
+
+        coordinator.commit(0, commit(&key_a)).unwrap();
+        assert_eq!(
+            coordinator.reveal(5, &key_a),
+            Err(RevealError::ParticipantOutOfRange)
+        );
+    }
+}
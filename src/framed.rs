@@ -0,0 +1,264 @@
+//! Pluggable byte-level compression for the response transcript.
+//!
+//! `Accumulator::serialize` already supports elliptic-curve point
+//! compression via `UseCompression`, but the raw bytes written to
+//! `challenge`/`response` are otherwise uncompressed. `FrameWriter`/
+//! `FrameReader` add a second, orthogonal compression layer: the byte
+//! stream is split into `FRAME_SIZE` (~16 MiB) frames, each independently
+//! compressed with a selectable `FrameCodec`, and prefixed with an
+//! `(uncompressed_len, compressed_len)` header so a reader can
+//! stream-decompress without buffering the whole file.
+//!
+//! Wrap a `FrameWriter` *inside* a `HashWriter` (hash first, compress
+//! second) so the BLAKE2b hash chain stays over the canonical uncompressed
+//! bytes regardless of codec -- contributors compare that hash to confirm
+//! their contribution made it into the transcript untouched, and it
+//! shouldn't change depending on which codec a participant's tooling picked.
+//!
+//! Not wired into `main`/`beacon`/`contribute_batched`'s own on-disk format:
+//! every one of them pre-computes an exact `challenge`/`response` byte size
+//! (`CeremonyParams::accumulator_byte_size_with_hash`/
+//! `contribution_byte_size`) and either checks a file against it up front or
+//! (`contribute_batched`) pre-sizes an `MmapMut` with it before writing a
+//! single byte. A `Zstd`/`Snappy` codec's compressed length isn't knowable
+//! before encoding, so framing those binaries' own output would mean
+//! replacing those exact-size checks everywhere -- a wire-format change
+//! across the whole ceremony, not a one-line addition here.
+//!
+//! Instead, `pack`/`unpack` wrap an already-finished `challenge`/`response`
+//! file for bandwidth-constrained transport without touching what
+//! `main`/`beacon`/`contribute_batched` themselves write, and `verify`
+//! reads a `.framed` file (one `pack` produced) straight through
+//! `FrameReader` -- genuinely streaming the decompression, since
+//! `Accumulator`/`PublicKey`'s derived `CanonicalDeserialize` reads its own
+//! length-prefixed `Vec`s and never needs the total file size up front. The
+//! one thing a `.framed` input gives up is `verify`'s up-front "is this
+//! file exactly the expected size" sanity check, since a compressed file's
+//! on-disk size isn't comparable to the uncompressed one -- malformed or
+//! truncated input still fails to deserialize, just without that early,
+//! friendlier error.
+
+use std::io::{self, Read, Write};
+
+/// Target, not exact, frame size: the last frame of a stream is usually
+/// shorter.
+#[cfg(not(test))]
+const FRAME_SIZE: usize = 16 * 1024 * 1024;
+
+/// Shrunk so the round-trip test below actually spans multiple frames
+/// without writing 16 MiB of test input.
+#[cfg(test)]
+const FRAME_SIZE: usize = 16;
+
+/// Byte-level compression codec for a single frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameCodec {
+    /// Pass-through, for compatibility with tooling that can't decompress.
+    None,
+    Zstd,
+    Snappy,
+}
+
+impl FrameCodec {
+    fn compress(self, data: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            FrameCodec::None => Ok(data.to_vec()),
+            FrameCodec::Zstd => zstd::stream::encode_all(data, 0),
+            FrameCodec::Snappy => snap::raw::Encoder::new()
+                .compress_vec(data)
+                .map_err(io::Error::other),
+        }
+    }
+
+    fn decompress(self, data: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            FrameCodec::None => Ok(data.to_vec()),
+            FrameCodec::Zstd => zstd::stream::decode_all(data),
+            FrameCodec::Snappy => snap::raw::Decoder::new()
+                .decompress_vec(data)
+                .map_err(io::Error::other),
+        }
+    }
+
+    fn tag(self) -> u8 {
+        match self {
+            FrameCodec::None => 0,
+            FrameCodec::Zstd => 1,
+            FrameCodec::Snappy => 2,
+        }
+    }
+
+    fn from_tag(tag: u8) -> io::Result<Self> {
+        match tag {
+            0 => Ok(FrameCodec::None),
+            1 => Ok(FrameCodec::Zstd),
+            2 => Ok(FrameCodec::Snappy),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "unknown frame codec tag",
+            )),
+        }
+    }
+}
+
+/// Buffers writes into `FRAME_SIZE` chunks, compressing and framing each one
+/// with `codec` as it fills. The final, possibly short, frame is only
+/// written on `flush` (or `Drop`), so callers that skip both will lose it --
+/// same caveat as `BufWriter`.
+pub struct FrameWriter<W: Write> {
+    inner: W,
+    codec: FrameCodec,
+    buf: Vec<u8>,
+}
+
+impl<W: Write> FrameWriter<W> {
+    pub fn new(inner: W, codec: FrameCodec) -> Self {
+        FrameWriter {
+            inner,
+            codec,
+            buf: Vec::with_capacity(FRAME_SIZE),
+        }
+    }
+
+    fn write_frame(&mut self) -> io::Result<()> {
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+        let compressed = self.codec.compress(&self.buf)?;
+        self.inner.write_all(&[self.codec.tag()])?;
+        self.inner.write_all(&(self.buf.len() as u64).to_le_bytes())?;
+        self.inner
+            .write_all(&(compressed.len() as u64).to_le_bytes())?;
+        self.inner.write_all(&compressed)?;
+        self.buf.clear();
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for FrameWriter<W> {
+    fn write(&mut self, mut data: &[u8]) -> io::Result<usize> {
+        let total = data.len();
+        while !data.is_empty() {
+            let space = FRAME_SIZE - self.buf.len();
+            let take = space.min(data.len());
+            self.buf.extend_from_slice(&data[..take]);
+            data = &data[take..];
+            if self.buf.len() == FRAME_SIZE {
+                self.write_frame()?;
+            }
+        }
+        Ok(total)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_frame()?;
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> Drop for FrameWriter<W> {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+/// Reads frames written by `FrameWriter` back into a plain byte stream,
+/// decompressing each one as it's reached.
+pub struct FrameReader<R: Read> {
+    inner: R,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl<R: Read> FrameReader<R> {
+    pub fn new(inner: R) -> Self {
+        FrameReader {
+            inner,
+            buf: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Reads and decompresses the next frame into `self.buf`. Returns `false`
+    /// at a clean end of stream (no bytes available for the next frame's tag).
+    fn read_frame(&mut self) -> io::Result<bool> {
+        let mut tag = [0u8; 1];
+        if self.inner.read(&mut tag)? == 0 {
+            return Ok(false);
+        }
+        let codec = FrameCodec::from_tag(tag[0])?;
+
+        let mut len_buf = [0u8; 8];
+        self.inner.read_exact(&mut len_buf)?;
+        let uncompressed_len = u64::from_le_bytes(len_buf) as usize;
+        self.inner.read_exact(&mut len_buf)?;
+        let compressed_len = u64::from_le_bytes(len_buf) as usize;
+
+        let mut compressed = vec![0u8; compressed_len];
+        self.inner.read_exact(&mut compressed)?;
+
+        let decompressed = codec.decompress(&compressed)?;
+        if decompressed.len() != uncompressed_len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "frame's decompressed length didn't match its header",
+            ));
+        }
+        self.buf = decompressed;
+        self.pos = 0;
+        Ok(true)
+    }
+}
+
+impl<R: Read> Read for FrameReader<R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        if self.pos >= self.buf.len() && !self.read_frame()? {
+            return Ok(0);
+        }
+        let n = (self.buf.len() - self.pos).min(out.len());
+        out[..n].copy_from_slice(&self.buf[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Input well over `FRAME_SIZE` (shrunk to 16 bytes under `#[cfg(test)]`),
+    /// so a round trip genuinely exercises more than one frame, not just the
+    /// final short one.
+    fn multi_frame_input() -> Vec<u8> {
+        (0..10 * FRAME_SIZE as u32)
+            .flat_map(|i| i.to_le_bytes())
+            .collect()
+    }
+
+    fn round_trip(codec: FrameCodec) {
+        let input = multi_frame_input();
+
+        let mut framed = Vec::new();
+        {
+            let mut writer = FrameWriter::new(&mut framed, codec);
+            writer.write_all(&input).expect("write");
+            writer.flush().expect("flush");
+        }
+
+        let mut reader = FrameReader::new(framed.as_slice());
+        let mut output = Vec::new();
+        reader.read_to_end(&mut output).expect("read");
+
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn round_trips_none_codec_across_multiple_frames() {
+        round_trip(FrameCodec::None);
+    }
+
+    #[test]
+    fn round_trips_zstd_codec_across_multiple_frames() {
+        round_trip(FrameCodec::Zstd);
+    }
+}
@@ -0,0 +1,995 @@
+use ark_ec::pairing::Pairing;
+use ark_ec::*;
+
+use ark_std::UniformRand;
+use num_traits::identities::Zero;
+use rayon::prelude::*;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+mod batched_accumulator;
+pub mod commit_reveal;
+pub mod framed;
+pub mod phase2;
+
+use batched_accumulator::BatchedAccumulator;
+
+/// Runs [`Accumulator::transform`] a fixed-size `batch_size` window at a
+/// time over a memory-mapped challenge/response pair instead of
+/// deserializing the whole accumulator into RAM first, for circuit sizes
+/// (2^26-2^28 points) too large to fit in memory whole. `input` must be laid
+/// out like an uncompressed challenge; `output` must have room for a 64-byte
+/// header followed by the accumulator in `output_compression` -- see
+/// `batched_accumulator` for how the windowing itself works. Generic over
+/// `P`, same as the rest of that module.
+///
+/// Returns `(current_accumulator_hash, output_hasher)`, exactly as
+/// `BatchedAccumulator::transform` does, so the caller can append the
+/// serialized public key to `output` and to `output_hasher` before
+/// finalizing the contribution hash.
+pub fn transform_batched<P: Pairing>(
+    input: &memmap2::Mmap,
+    output: &mut memmap2::MmapMut,
+    key: &PrivateKey<P>,
+    params: &CeremonyParams<P>,
+    batch_size: usize,
+    output_compression: UseCompression,
+) -> io::Result<(GenericArray<u8, U64>, Blake2b512)> {
+    BatchedAccumulator::new(batch_size).transform(input, output, key, params, output_compression)
+}
+
+/// Windowed counterpart to `check_power_ratios`: runs the same `power_pairs`
+/// consistency checks against a memory-mapped `response` a `batch_size`
+/// window at a time, via `BatchedAccumulator::windowed_power_pairs`, instead
+/// of deserializing the whole accumulator into RAM first. `compression` must
+/// match however `response` was written.
+pub fn verify_batched_power_ratios<P: Pairing>(
+    response: &memmap2::Mmap,
+    params: &CeremonyParams<P>,
+    batch_size: usize,
+    compression: UseCompression,
+) -> bool {
+    BatchedAccumulator::new(batch_size).verify_power_ratios(response, params, compression)
+}
+
+pub struct Sizes<P: Pairing> {
+    g1_uncompressed_byte_size: usize,
+    g2_uncompressed_byte_size: usize,
+    g1_compressed_byte_size: usize,
+    g2_compressed_byte_size: usize,
+    _curve: PhantomData<P>,
+}
+
+impl<P: Pairing> Default for Sizes<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: Pairing> Sizes<P> {
+    pub fn new() -> Self {
+        let g1 = <P as Pairing>::G1Affine::zero();
+        let g2 = <P as Pairing>::G2Affine::zero();
+        Self {
+            g1_uncompressed_byte_size: g1.uncompressed_size(),
+            g2_uncompressed_byte_size: g2.uncompressed_size(),
+            g1_compressed_byte_size: g1.compressed_size(),
+            g2_compressed_byte_size: g2.compressed_size(),
+            _curve: PhantomData,
+        }
+    }
+
+    ///// The "public key" is used to verify a contribution was correctly
+    /// computed.
+    pub fn public_key_size(&self) -> usize {
+        PublicKey::<P>::default().uncompressed_size()
+    }
+}
+
+/// Runtime parameters of a single ceremony: the circuit size plus the
+/// derived vector lengths and point sizes for the pairing `P`. Replacing the
+/// old `TAU_POWERS_LENGTH`/`TAU_POWERS_G1_LENGTH` constants with a value
+/// threaded through `Accumulator`/`PublicKey`/`PrivateKey`/`keypair`/
+/// `verify_transform` lets the same binary run a tiny test ceremony and a
+/// production ceremony -- of any `size` -- without recompilation. `P` itself
+/// is still a compile-time generic: switching curves means recompiling
+/// against a different monomorphization, the same as before this type
+/// existed.
+pub struct CeremonyParams<P: Pairing> {
+    /// log2 of the number of multiplication gates the circuit supports.
+    pub size: usize,
+    /// 2^size: the number of tau powers needed in G2, alpha*tau and beta*tau.
+    pub tau_powers_length: usize,
+    /// 2 * tau_powers_length - 1: tau powers needed in G1 (see the doc
+    /// comment that used to sit on `TAU_POWERS_G1_LENGTH`).
+    pub tau_powers_g1_length: usize,
+    /// Point byte sizes for `P`.
+    pub sizes: Sizes<P>,
+}
+
+impl<P: Pairing> CeremonyParams<P> {
+    pub fn new(size: usize) -> Self {
+        let tau_powers_length = 1 << size;
+        CeremonyParams {
+            size,
+            tau_powers_length,
+            tau_powers_g1_length: (tau_powers_length << 1) - 1,
+            sizes: Sizes::new(),
+        }
+    }
+
+    /// The size of the accumulator on disk.
+    pub fn accumulator_byte_size_with_hash(&self) -> usize {
+        (self.tau_powers_g1_length * self.sizes.g1_uncompressed_byte_size) + // g1 tau powers
+        (self.tau_powers_length * self.sizes.g2_uncompressed_byte_size) + // g2 tau powers
+        (self.tau_powers_length * self.sizes.g1_uncompressed_byte_size) + // alpha tau powers
+        (self.tau_powers_length * self.sizes.g1_uncompressed_byte_size) // beta tau powers
+        + 32 // lengths of vectors
+        + self.sizes.g2_uncompressed_byte_size // beta in g2
+        + 64 // blake2b hash of previous contribution
+    }
+
+    /// The size of the contribution on disk.
+    pub fn contribution_byte_size(&self) -> usize {
+        (self.tau_powers_g1_length * self.sizes.g1_compressed_byte_size) + // g1 tau powers
+        (self.tau_powers_length * self.sizes.g2_compressed_byte_size) + // g2 tau powers
+        (self.tau_powers_length * self.sizes.g1_compressed_byte_size) + // alpha tau powers
+        (self.tau_powers_length * self.sizes.g1_compressed_byte_size) // beta tau powers
+        + 32 // lengths of vectors
+        + self.sizes.g2_compressed_byte_size // beta in g2
+        + 64 // blake2b hash of input accumulator
+        + self.sizes.public_key_size() // public key
+    }
+}
+
+/// Checks if pairs have the same ratio.
+pub fn same_ratio<P: Pairing>(g1: (P::G1Affine, P::G1Affine), g2: (P::G2Affine, P::G2Affine)) -> bool {
+    P::pairing(g1.0, g2.1) == P::pairing(g1.1, g2.0)
+}
+
+/// Computes a random linear combination over v1/v2.
+///
+/// Checking that many pairs of elements are exponentiated by
+/// the same `x` can be achieved (with high probability) with
+/// the following technique:
+///
+/// Given v1 = [a, b, c] and v2 = [as, bs, cs], compute
+/// (a*r1 + b*r2 + c*r3, (as)*r1 + (bs)*r2 + (cs)*r3) for some
+/// random r1, r2, r3. Given (g, g^s)...
+///
+/// e(g, (as)*r1 + (bs)*r2 + (cs)*r3) = e(g^s, a*r1 + b*r2 + c*r3)
+///
+/// ... with high probability.
+fn merge_pairs<G: AffineRepr>(v1: &[G], v2: &[G]) -> (G, G) {
+    use rand::thread_rng;
+
+    assert_eq!(v1.len(), v2.len());
+
+    let chunk_size = (v1.len() / num_cpus::get()) + 1;
+
+    let s = Arc::new(Mutex::new(G::Group::zero()));
+    let sx = Arc::new(Mutex::new(G::Group::zero()));
+
+    v1.par_chunks(chunk_size)
+        .zip(v2.par_chunks(chunk_size))
+        .for_each(|(v1, v2)| {
+            let s = s.clone();
+            let sx = sx.clone();
+
+            // We do not need to be overly cautious of the RNG
+            // used for this check.
+            let rng = &mut thread_rng();
+
+            let mut local_s = G::Group::zero();
+            let mut local_sx = G::Group::zero();
+
+            for (v1, v2) in v1.iter().zip(v2.iter()) {
+                let rho = G::ScalarField::rand(rng);
+                let v1 = *v1 * rho;
+                let v2 = *v2 * rho;
+
+                local_s += v1;
+                local_sx += v2;
+            }
+
+            *s.lock().unwrap() += local_s;
+            *sx.lock().unwrap() += local_sx;
+        });
+
+    let s = s.lock().unwrap().into_affine();
+    let sx = sx.lock().unwrap().into_affine();
+
+    (s, sx)
+}
+
+/// Construct a single pair (s, s^x) for a vector of
+/// the form [1, x, x^2, x^3, ...].
+pub fn power_pairs<G: AffineRepr>(v: &[G]) -> (G, G) {
+    merge_pairs(&v[0..(v.len() - 1)], &v[1..])
+}
+
+/// Exponentiate a large number of points, with an optional coefficient to be applied to the
+/// exponent.
+fn batch_exp<G: AffineRepr>(bases: &mut [G], exp: &[G::ScalarField], coeff: Option<&G::ScalarField>) {
+    assert_eq!(bases.len(), exp.len());
+
+    // Perform wNAF over multiple cores, placing results into `projective`.
+    let projective: Vec<_> = bases
+        .par_iter()
+        .zip(exp)
+        .map(|(base, exp)| {
+            let mut exp = *exp;
+            if let Some(coeff) = coeff {
+                exp *= coeff;
+            }
+
+            // PITODO: base * exp, check if arkworks does that efficiently already
+            // or whether we need to use some scalar-mul thingy
+            *base * exp
+        })
+        .collect();
+
+    // Perform batch normalization
+    // Turn it all back into affine points
+    let affine = G::Group::normalize_batch(&projective);
+    bases.copy_from_slice(&affine);
+}
+
+//----------------------------------------------
+// 2nd Week
+//----------------------------------------------
+use ark_serialize::CanonicalSerialize;
+// use ark_serialize::*;
+use blake2::{Blake2b512, Digest};
+use generic_array::GenericArray;
+use typenum::consts::U64;
+
+/// Compute BLAKE2b("")
+pub fn blank_hash() -> GenericArray<u8, U64> {
+    Blake2b512::new().finalize()
+}
+
+/// Whether elliptic curve points should be serialized/deserialized in their
+/// compressed (roughly half the size, more expensive to decompress) or
+/// uncompressed form. Lets participants download an uncompressed `challenge`
+/// but upload a compressed `response`, roughly halving upload size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UseCompression {
+    Yes,
+    No,
+}
+
+impl From<UseCompression> for Compress {
+    fn from(compression: UseCompression) -> Compress {
+        match compression {
+            UseCompression::Yes => Compress::Yes,
+            UseCompression::No => Compress::No,
+        }
+    }
+}
+
+/// Whether deserialization should validate that each point is on the curve,
+/// in the correct subgroup, and not the identity. Skipping the check is only
+/// safe for points that are already trusted, e.g. a `challenge` this process
+/// just wrote itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckForCorrectness {
+    Yes,
+    No,
+}
+
+impl From<CheckForCorrectness> for Validate {
+    fn from(check: CheckForCorrectness) -> Validate {
+        match check {
+            CheckForCorrectness::Yes => Validate::Yes,
+            CheckForCorrectness::No => Validate::No,
+        }
+    }
+}
+
+/// The `Accumulator` is an object that participants of the ceremony contribute
+/// randomness to. This object contains powers of trapdoor `tau` in G1 and in G2 over
+/// fixed generators, and additionally in G1 over two other generators of exponents
+/// `alpha` and `beta` over those fixed generators. In other words:
+///
+/// * (τ, τ<sup>2</sup>, ..., τ<sup>2m - 2</sup>, α, ατ, ατ<sup>2</sup>, ..., ατ<sup>m - 1</sup>, β, βτ, βτ<sup>2</sup>, ..., βτ<sup>m - 1</sup>)<sub>1</sub>
+/// * (β, τ, τ<sup>2</sup>, ..., τ<sup>m - 1</sup>)<sub>2</sub>
+///
+/// where `m = params.tau_powers_length`. `P` is the pairing the ceremony
+/// runs over; `params: &CeremonyParams<P>` supplies the circuit-dependent
+/// vector lengths that used to be the fixed `TAU_POWERS_LENGTH`/
+/// `TAU_POWERS_G1_LENGTH` constants.
+#[derive(PartialEq, Eq, Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Accumulator<P: Pairing> {
+    /// tau^0, tau^1, tau^2, ..., tau^{tau_powers_g1_length - 1}
+    pub tau_powers_g1: Vec<P::G1Affine>,
+    /// tau^0, tau^1, tau^2, ..., tau^{tau_powers_length - 1}
+    pub tau_powers_g2: Vec<P::G2Affine>,
+    /// alpha * tau^0, alpha * tau^1, alpha * tau^2, ..., alpha * tau^{tau_powers_length - 1}
+    pub alpha_tau_powers_g1: Vec<P::G1Affine>,
+    /// beta * tau^0, beta * tau^1, beta * tau^2, ..., beta * tau^{tau_powers_length - 1}
+    pub beta_tau_powers_g1: Vec<P::G1Affine>,
+    /// beta
+    pub beta_g2: P::G2Affine,
+}
+
+impl<P: Pairing> Accumulator<P> {
+    /// Constructs an "initial" accumulator with τ = 1, α = 1, β = 1.
+    pub fn new(params: &CeremonyParams<P>) -> Self {
+        Accumulator {
+            tau_powers_g1: vec![P::G1Affine::generator(); params.tau_powers_g1_length],
+            tau_powers_g2: vec![P::G2Affine::generator(); params.tau_powers_length],
+            alpha_tau_powers_g1: vec![P::G1Affine::generator(); params.tau_powers_length],
+            beta_tau_powers_g1: vec![P::G1Affine::generator(); params.tau_powers_length],
+            beta_g2: P::G2Affine::generator(),
+        }
+    }
+
+    /// Transforms the accumulator with a private key.
+    /// tau, tau^2, tau^3,...
+    /// t, t^2, t^3,...
+    /// tau^t, (tau^2)^(t^2),...
+    pub fn transform(&mut self, key: &PrivateKey<P>, params: &CeremonyParams<P>) {
+        // Construct the powers of tau
+        let mut taupowers = vec![P::ScalarField::zero(); params.tau_powers_g1_length];
+        let chunk_size = params.tau_powers_g1_length / num_cpus::get();
+
+        // Construct exponents in parallel
+        taupowers
+            .par_chunks_mut(chunk_size)
+            .enumerate()
+            .for_each(|(i, taupowers)| {
+                let mut acc = key.tau.pow([(i * chunk_size) as u64]);
+
+                for t in taupowers {
+                    *t = acc;
+                    acc *= key.tau;
+                }
+            });
+
+        batch_exp(&mut self.tau_powers_g1, &taupowers[0..], None);
+        batch_exp(
+            &mut self.tau_powers_g2,
+            &taupowers[0..params.tau_powers_length],
+            None,
+        );
+        batch_exp(
+            &mut self.alpha_tau_powers_g1,
+            &taupowers[0..params.tau_powers_length],
+            Some(&key.alpha),
+        );
+        batch_exp(
+            &mut self.beta_tau_powers_g1,
+            &taupowers[0..params.tau_powers_length],
+            Some(&key.beta),
+        );
+        self.beta_g2 = (self.beta_g2 * key.beta).into_affine();
+    }
+
+    /// Serializes the accumulator, using `compression` to choose between the
+    /// full-size `challenge` format and the half-size `response` format.
+    pub fn serialize<W: Write>(
+        &self,
+        writer: &mut W,
+        compression: UseCompression,
+    ) -> io::Result<()> {
+        self.serialize_with_mode(writer, compression.into())
+            .map_err(io::Error::other)
+    }
+
+    /// Deserializes the accumulator. With `CheckForCorrectness::Yes`, every
+    /// point is validated to be on the curve, in the correct subgroup, and
+    /// not the identity; with `No`, validation is skipped, which is only
+    /// safe for points that are already trusted. `compression` must match
+    /// however the bytes were written by `serialize`; a compressed `after`
+    /// can still be compared against an uncompressed `before` once both have
+    /// been deserialized into `Accumulator`s.
+    pub fn deserialize<R: Read>(
+        reader: &mut R,
+        compression: UseCompression,
+        check: CheckForCorrectness,
+    ) -> io::Result<Self> {
+        let acc = Self::deserialize_with_mode(reader, compression.into(), check.into())
+            .map_err(io::Error::other)?;
+
+        if check == CheckForCorrectness::Yes && acc.contains_identity() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "accumulator contains an identity point",
+            ));
+        }
+
+        Ok(acc)
+    }
+
+    /// Whether any point in the accumulator is the identity. `deserialize_with_mode`'s
+    /// subgroup check accepts the identity as a valid subgroup member, so
+    /// `CheckForCorrectness::Yes` has to reject it separately -- see the
+    /// `PublicKey` doc comment for why no point in a contribution may be it.
+    fn contains_identity(&self) -> bool {
+        self.tau_powers_g1.iter().any(|p| p.is_zero())
+            || self.tau_powers_g2.iter().any(|p| p.is_zero())
+            || self.alpha_tau_powers_g1.iter().any(|p| p.is_zero())
+            || self.beta_tau_powers_g1.iter().any(|p| p.is_zero())
+            || self.beta_g2.is_zero()
+    }
+}
+
+//----------------------------------------------
+// 3rd Week
+//----------------------------------------------
+use ark_serialize::{CanonicalDeserialize, Compress, Validate};
+
+use std::io::{self, Read, Write};
+use rand::Rng;
+use rand::SeedableRng;
+use rand_chacha::ChaChaRng;
+
+use ark_ff::fields::Field;
+//----------------------------------------------
+
+// /// The size of the accumulator on disk.
+// pub fn accumulator_byte_size_with_hash(&self) -> usize {
+//     (TAU_POWERS_G1_LENGTH * self.g1_uncompressed_byte_size) + // g1 tau powers
+//     (TAU_POWERS_LENGTH * self.g2_uncompressed_byte_size) + // g2 tau powers
+//     (TAU_POWERS_LENGTH * self.g1_uncompressed_byte_size) + // alpha tau powers
+//     (TAU_POWERS_LENGTH * self.g1_uncompressed_byte_size) // beta tau powers
+//     + 32 // lengths of vectors
+//     + self.g2_uncompressed_byte_size // beta in g2
+//     + 64 // blake2b hash of previous contribution
+// }
+
+/// Abstraction over a reader which hashes the data being read.
+pub struct HashReader<R: Read> {
+    reader: R,
+    hasher: Blake2b512,
+}
+
+impl<R: Read> HashReader<R> {
+    /// Construct a new `HashReader` given an existing `reader` by value.
+    pub fn new(reader: R) -> Self {
+        HashReader {
+            reader,
+            hasher: Blake2b512::default(),
+        }
+    }
+
+    /// Destroy this reader and return the hash of what was read.
+    pub fn into_hash(self) -> GenericArray<u8, U64> {
+        self.hasher.finalize()
+    }
+}
+
+impl<R: Read> Read for HashReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let bytes = self.reader.read(buf)?;
+
+        if bytes > 0 {
+            self.hasher.update(&buf[0..bytes]);
+        }
+
+        Ok(bytes)
+    }
+}
+
+/// Abstraction over a writer which hashes the data being written.
+pub struct HashWriter<W: Write> {
+    writer: W,
+    hasher: Blake2b512,
+}
+
+impl<W: Write> HashWriter<W> {
+    /// Construct a new `HashWriter` given an existing `writer` by value.
+    pub fn new(writer: W) -> Self {
+        HashWriter {
+            writer,
+            hasher: Blake2b512::default(),
+        }
+    }
+
+    /// Destroy this writer and return the hash of what was written.
+    pub fn into_hash(self) -> GenericArray<u8, U64> {
+        self.hasher.finalize()
+    }
+}
+
+impl<W: Write> Write for HashWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let bytes = self.writer.write(buf)?;
+
+        if bytes > 0 {
+            self.hasher.update(&buf[0..bytes]);
+        }
+
+        Ok(bytes)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Hashes to G2 using the first 32 bytes of `digest`. Panics if `digest` is less
+/// than 32 bytes.
+fn hash_to_g2<P: Pairing>(digest: &[u8]) -> P::G2 {
+    assert!(digest.len() >= 32);
+
+    let mut seed = [0; 32];
+    seed.copy_from_slice(&digest[..32]);
+
+    P::G2::rand(&mut ChaChaRng::from_seed(seed))
+}
+
+/// Contains terms of the form (s<sub>1</sub>, s<sub>1</sub><sup>x</sup>, H(s<sub>1</sub><sup>x</sup>)<sub>2</sub>, H(s<sub>1</sub><sup>x</sup>)<sub>2</sub><sup>x</sup>)
+/// for all x in τ, α and β, and some s chosen randomly by its creator. The function H "hashes into" the group G2. No points in the public key may be the identity.
+///
+/// The elements in G2 are used to verify transformations of the accumulator. By its nature, the public key proves
+/// knowledge of τ, α and β.
+///
+/// It is necessary to verify `same_ratio`((s<sub>1</sub>, s<sub>1</sub><sup>x</sup>), (H(s<sub>1</sub><sup>x</sup>)<sub>2</sub>, H(s<sub>1</sub><sup>x</sup>)<sub>2</sub><sup>x</sup>)).
+#[derive(PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PublicKey<P: Pairing> {
+    tau_g1: (P::G1Affine, P::G1Affine),
+    alpha_g1: (P::G1Affine, P::G1Affine),
+    beta_g1: (P::G1Affine, P::G1Affine),
+    tau_g2: P::G2Affine,
+    alpha_g2: P::G2Affine,
+    beta_g2: P::G2Affine,
+}
+
+impl<P: Pairing> Default for PublicKey<P> {
+    fn default() -> Self {
+        PublicKey {
+            tau_g1: (P::G1Affine::zero(), P::G1Affine::zero()),
+            alpha_g1: (P::G1Affine::zero(), P::G1Affine::zero()),
+            beta_g1: (P::G1Affine::zero(), P::G1Affine::zero()),
+            tau_g2: P::G2Affine::zero(),
+            alpha_g2: P::G2Affine::zero(),
+            beta_g2: P::G2Affine::zero(),
+        }
+    }
+}
+
+impl<P: Pairing> PublicKey<P> {
+    /// Deserializes a public key, always uncompressed (as `serialize_uncompressed`
+    /// writes it). With `CheckForCorrectness::Yes`, every point is validated to
+    /// be on the curve and in the correct subgroup, and none may be the
+    /// identity -- the derived `Valid::check` accepts the identity as a valid
+    /// subgroup member, so it's rejected here explicitly, same as
+    /// `Accumulator::deserialize`.
+    pub fn deserialize<R: Read>(reader: &mut R, check: CheckForCorrectness) -> io::Result<Self> {
+        let key = Self::deserialize_with_mode(reader, Compress::No, check.into()).map_err(io::Error::other)?;
+
+        if check == CheckForCorrectness::Yes && key.contains_identity() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "public key contains an identity point",
+            ));
+        }
+
+        Ok(key)
+    }
+
+    /// Whether any point in the public key is the identity -- see the struct
+    /// doc comment for why none may be.
+    fn contains_identity(&self) -> bool {
+        self.tau_g1.0.is_zero()
+            || self.tau_g1.1.is_zero()
+            || self.alpha_g1.0.is_zero()
+            || self.alpha_g1.1.is_zero()
+            || self.beta_g1.0.is_zero()
+            || self.beta_g1.1.is_zero()
+            || self.tau_g2.is_zero()
+            || self.alpha_g2.is_zero()
+            || self.beta_g2.is_zero()
+    }
+}
+
+/// Contains the secrets τ, α and β that the participant of the ceremony must destroy.
+pub struct PrivateKey<P: Pairing> {
+    tau: P::ScalarField,
+    alpha: P::ScalarField,
+    beta: P::ScalarField,
+}
+
+/// Constructs a keypair given an RNG and a 64-byte transcript `digest`.
+pub fn keypair<P: Pairing, R: Rng>(rng: &mut R, digest: &[u8]) -> (PublicKey<P>, PrivateKey<P>) {
+    assert_eq!(digest.len(), 64);
+
+    let tau = P::ScalarField::rand(rng);
+    let alpha = P::ScalarField::rand(rng);
+    let beta = P::ScalarField::rand(rng);
+
+    let mut op = |x: P::ScalarField, personalization: u8| {
+        // Sample random g^s
+        let g1_s = P::G1::rand(rng).into_affine();
+        // Compute g^{s*x}
+        let g1_s_x = (g1_s * x).into_affine();
+        // Compute BLAKE2b(personalization | transcript | g^s | g^{s*x})
+        let h = {
+            let mut h = Blake2b512::default();
+            h.update([personalization]);
+            h.update(digest);
+            g1_s.serialize_uncompressed(&mut h).unwrap();
+            g1_s_x.serialize_uncompressed(&mut h).unwrap();
+            h.finalize()
+        };
+        // Hash into G2 as g^{s'}
+        let g2_s = hash_to_g2::<P>(h.as_ref()).into_affine();
+        // Compute g^{s'*x}
+        let g2_s_x = (g2_s * x).into_affine();
+
+        ((g1_s, g1_s_x), g2_s_x)
+    };
+
+    let pk_tau = op(tau, 0);
+    let pk_alpha = op(alpha, 1);
+    let pk_beta = op(beta, 2);
+
+    (
+        PublicKey {
+            tau_g1: pk_tau.0,
+            alpha_g1: pk_alpha.0,
+            beta_g1: pk_beta.0,
+            tau_g2: pk_tau.1,
+            alpha_g2: pk_alpha.1,
+            beta_g2: pk_beta.1,
+        },
+        PrivateKey { tau, alpha, beta },
+    )
+}
+
+// Verifies a transformation of the `Accumulator` with the `PublicKey`, given a 64-byte transcript `digest`.
+// `before` and `after` need not have been read from disk with the same
+// `UseCompression`; `Accumulator::deserialize` decompresses compressed
+// points up front, so by the time they reach this function both are plain
+// `Accumulator`s regardless of their on-disk format.
+pub fn verify_transform<P: Pairing>(
+    before: &Accumulator<P>,
+    after: &Accumulator<P>,
+    key: &PublicKey<P>,
+    digest: &[u8],
+) -> bool {
+    verify_transform_detailed(before, after, key, digest).is_ok()
+}
+
+/// Why a single ceremony step failed to verify, so a coordinator walking a
+/// whole transcript with [`verify_transcript`] can pinpoint which
+/// participant's contribution broke the chain instead of getting a single
+/// bare `bool`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerificationError {
+    /// The "hash of previous contribution" embedded in this step's response
+    /// doesn't match the BLAKE2b hash of the accumulator it claims to build on.
+    WrongPreviousHash,
+    /// The proof of knowledge of the new `tau` failed.
+    BadPokTau,
+    /// The proof of knowledge of the new `alpha` failed.
+    BadPokAlpha,
+    /// The proof of knowledge of the new `beta` failed.
+    BadPokBeta,
+    /// `tau_powers_g1[0]`/`tau_powers_g2[0]` wasn't the curve generator.
+    NonGeneratorFirstElement,
+    /// The `power_pairs` consistency check on the resulting accumulator failed.
+    BadPowerRatio,
+}
+
+/// Same checks as [`verify_transform`], but reporting which check failed
+/// instead of collapsing everything into a `bool`.
+pub fn verify_transform_detailed<P: Pairing>(
+    before: &Accumulator<P>,
+    after: &Accumulator<P>,
+    key: &PublicKey<P>,
+    digest: &[u8],
+) -> Result<(), VerificationError> {
+    assert_eq!(digest.len(), 64);
+
+    let compute_g2_s = |g1_s: P::G1Affine, g1_s_x: P::G1Affine, personalization: u8| {
+        let mut h = Blake2b512::default();
+        h.update([personalization]);
+        h.update(digest);
+        g1_s.serialize_uncompressed(&mut h).unwrap();
+        g1_s_x.serialize_uncompressed(&mut h).unwrap();
+        hash_to_g2::<P>(h.finalize().as_ref()).into_affine()
+    };
+
+    let tau_g2_s = compute_g2_s(key.tau_g1.0, key.tau_g1.1, 0);
+    let alpha_g2_s = compute_g2_s(key.alpha_g1.0, key.alpha_g1.1, 1);
+    let beta_g2_s = compute_g2_s(key.beta_g1.0, key.beta_g1.1, 2);
+
+    // Check the proofs-of-knowledge for tau/alpha/beta
+    if !same_ratio::<P>(key.tau_g1, (tau_g2_s, key.tau_g2)) {
+        return Err(VerificationError::BadPokTau);
+    }
+    if !same_ratio::<P>(key.alpha_g1, (alpha_g2_s, key.alpha_g2)) {
+        return Err(VerificationError::BadPokAlpha);
+    }
+    if !same_ratio::<P>(key.beta_g1, (beta_g2_s, key.beta_g2)) {
+        return Err(VerificationError::BadPokBeta);
+    }
+
+    // Check the correctness of the generators for tau powers
+    if after.tau_powers_g1[0] != P::G1Affine::generator() {
+        return Err(VerificationError::NonGeneratorFirstElement);
+    }
+    if after.tau_powers_g2[0] != P::G2Affine::generator() {
+        return Err(VerificationError::NonGeneratorFirstElement);
+    }
+
+    // Did the participant multiply the previous tau by the new one?
+    if !same_ratio::<P>(
+        (before.tau_powers_g1[1], after.tau_powers_g1[1]),
+        (tau_g2_s, key.tau_g2),
+    ) {
+        return Err(VerificationError::BadPokTau);
+    }
+
+    // Did the participant multiply the previous alpha by the new one?
+    if !same_ratio::<P>(
+        (before.alpha_tau_powers_g1[0], after.alpha_tau_powers_g1[0]),
+        (alpha_g2_s, key.alpha_g2),
+    ) {
+        return Err(VerificationError::BadPokAlpha);
+    }
+
+    // Did the participant multiply the previous beta by the new one?
+    if !same_ratio::<P>(
+        (before.beta_tau_powers_g1[0], after.beta_tau_powers_g1[0]),
+        (beta_g2_s, key.beta_g2),
+    ) {
+        return Err(VerificationError::BadPokBeta);
+    }
+    if !same_ratio::<P>(
+        (before.beta_tau_powers_g1[0], after.beta_tau_powers_g1[0]),
+        (before.beta_g2, after.beta_g2),
+    ) {
+        return Err(VerificationError::BadPokBeta);
+    }
+
+    // Are the powers of tau correct?
+    check_power_ratios(after)
+}
+
+/// The `power_pairs` consistency checks `verify_transform_detailed` runs on
+/// the resulting accumulator, factored out so [`verify_transcript`] can also
+/// run them once on the final accumulator in the chain.
+fn check_power_ratios<P: Pairing>(acc: &Accumulator<P>) -> Result<(), VerificationError> {
+    if !same_ratio::<P>(
+        power_pairs(&acc.tau_powers_g1),
+        (acc.tau_powers_g2[0], acc.tau_powers_g2[1]),
+    ) {
+        return Err(VerificationError::BadPowerRatio);
+    }
+    if !same_ratio::<P>(
+        (acc.tau_powers_g1[0], acc.tau_powers_g1[1]),
+        power_pairs(&acc.tau_powers_g2),
+    ) {
+        return Err(VerificationError::BadPowerRatio);
+    }
+    if !same_ratio::<P>(
+        power_pairs(&acc.alpha_tau_powers_g1),
+        (acc.tau_powers_g2[0], acc.tau_powers_g2[1]),
+    ) {
+        return Err(VerificationError::BadPowerRatio);
+    }
+    if !same_ratio::<P>(
+        power_pairs(&acc.beta_tau_powers_g1),
+        (acc.tau_powers_g2[0], acc.tau_powers_g2[1]),
+    ) {
+        return Err(VerificationError::BadPowerRatio);
+    }
+
+    Ok(())
+}
+
+/// One participant's contribution to a ceremony transcript, as read back off
+/// disk: the accumulator they produced, their `PublicKey`, the 64-byte "hash
+/// of previous contribution" their `response` file embeds ahead of it (the
+/// same bytes `main`'s `current_accumulator_hash` writes out), and the
+/// BLAKE2b hash of the whole `response` file itself -- the same value
+/// `main`'s `contribution_hash` prints -- which becomes the header of the
+/// next contributor's challenge.
+pub struct Contribution<P: Pairing> {
+    pub accumulator: Accumulator<P>,
+    pub public_key: PublicKey<P>,
+    pub previous_hash: GenericArray<u8, U64>,
+    pub response_hash: GenericArray<u8, U64>,
+}
+
+/// Walks a whole ceremony transcript end to end, one entry per contribution,
+/// instead of the single before/after step `verify_transform` checks.
+///
+/// For step `i` this recomputes the BLAKE2b hash that `keypair` (and, at
+/// verification time, `verify_transform`) were run against -- the hash of the
+/// *whole* challenge file step `i` read, 64-byte header and accumulator
+/// together, exactly as `HashReader` would have produced had it read that
+/// challenge off disk -- and confirms it matches the "hash of previous
+/// contribution" embedded in contribution `i`'s response. The header of step
+/// `i`'s challenge is `initial_hash` for the first step, and the previous
+/// contribution's own `response_hash` (the whole-file hash of the prior
+/// `response`) for every step after that, carrying the chain forward the same
+/// way a `new_challenge` step would. If it matches, `verify_transform_detailed`
+/// is run for that step. The last step's success already implies the final
+/// accumulator's `power_pairs` ratios are correct, since
+/// `verify_transform_detailed` checks those on `after`.
+///
+/// A step's result never stops the walk -- even on `WrongPreviousHash`,
+/// the next step still carries this step's `accumulator`/`response_hash`
+/// forward, so a caller gets one `Result` per contribution and can report
+/// exactly which one(s) broke the chain instead of only the first.
+pub fn verify_transcript<P: Pairing>(
+    initial: &Accumulator<P>,
+    initial_hash: GenericArray<u8, U64>,
+    contributions: &[Contribution<P>],
+) -> Vec<Result<(), VerificationError>> {
+    let mut previous = initial;
+    let mut previous_header = initial_hash;
+    let mut results = Vec::with_capacity(contributions.len());
+
+    for contribution in contributions {
+        let mut hasher = HashWriter::new(io::sink());
+        hasher
+            .write_all(previous_header.as_slice())
+            .expect("unable to hash previous challenge header");
+        previous
+            .serialize(&mut hasher, UseCompression::No)
+            .expect("unable to hash previous accumulator");
+        let expected_hash = hasher.into_hash();
+
+        if expected_hash.as_slice() != contribution.previous_hash.as_slice() {
+            results.push(Err(VerificationError::WrongPreviousHash));
+            previous = &contribution.accumulator;
+            previous_header = contribution.response_hash;
+            continue;
+        }
+
+        results.push(verify_transform_detailed(
+            previous,
+            &contribution.accumulator,
+            &contribution.public_key,
+            expected_hash.as_ref(),
+        ));
+        previous = &contribution.accumulator;
+        previous_header = contribution.response_hash;
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_mnt6_753::MNT6_753;
+    use ark_std::One;
+    use rand::thread_rng;
+
+    /// The digest `verify_transcript` recomputes for the step that reads a
+    /// challenge with header `header` and accumulator `acc` -- the same hash
+    /// `main`'s `HashReader` produces off disk, and so the same digest
+    /// `keypair` was run against for that step.
+    fn header_hash<P: Pairing>(
+        header: &GenericArray<u8, U64>,
+        acc: &Accumulator<P>,
+    ) -> GenericArray<u8, U64> {
+        let mut hasher = HashWriter::new(io::sink());
+        hasher
+            .write_all(header.as_slice())
+            .expect("unable to hash header");
+        acc.serialize(&mut hasher, UseCompression::No)
+            .expect("unable to hash accumulator");
+        hasher.into_hash()
+    }
+
+    /// The whole-`response`-file hash `main`'s `contribution_hash` prints --
+    /// the value a contribution's `response_hash` carries forward as the
+    /// next step's header.
+    fn response_file_hash<P: Pairing>(
+        previous_hash: &GenericArray<u8, U64>,
+        acc: &Accumulator<P>,
+        key: &PublicKey<P>,
+    ) -> GenericArray<u8, U64> {
+        let mut hasher = HashWriter::new(io::sink());
+        hasher
+            .write_all(previous_hash.as_slice())
+            .expect("unable to hash previous hash");
+        acc.serialize(&mut hasher, UseCompression::Yes)
+            .expect("unable to hash accumulator");
+        key.serialize_uncompressed(&mut hasher)
+            .expect("unable to hash public key");
+        hasher.into_hash()
+    }
+
+    /// Builds one honest step on top of `previous`/`previous_header`, the
+    /// same way `main` would: derive the digest, run `keypair` against it,
+    /// transform a clone of `previous`, and package it up as a `Contribution`
+    /// whose `previous_hash`/`response_hash` are exactly what `verify_transcript`
+    /// expects to recompute.
+    fn honest_step(
+        previous: &Accumulator<MNT6_753>,
+        previous_header: &GenericArray<u8, U64>,
+        params: &CeremonyParams<MNT6_753>,
+    ) -> Contribution<MNT6_753> {
+        let digest = header_hash(previous_header, previous);
+        let (public_key, private_key) =
+            keypair::<MNT6_753, _>(&mut thread_rng(), digest.as_ref());
+
+        let mut accumulator = previous.clone();
+        accumulator.transform(&private_key, params);
+
+        let response_hash = response_file_hash(&digest, &accumulator, &public_key);
+
+        Contribution {
+            accumulator,
+            public_key,
+            previous_hash: digest,
+            response_hash,
+        }
+    }
+
+    /// A fully honest two-step transcript verifies `Ok` at every step.
+    #[test]
+    fn honest_transcript_verifies() {
+        let params = CeremonyParams::<MNT6_753>::new(3);
+        let initial_hash = blank_hash();
+        let initial = Accumulator::<MNT6_753>::new(&params);
+
+        let step1 = honest_step(&initial, &initial_hash, &params);
+        let step2 = honest_step(&step1.accumulator, &step1.response_hash, &params);
+
+        let results = verify_transcript(&initial, initial_hash, &[step1, step2]);
+        assert_eq!(results, vec![Ok(()), Ok(())]);
+    }
+
+    /// A tampered `previous_hash` on one step fails only that step -- the
+    /// walk keeps going and still checks the remaining steps, so a
+    /// coordinator sees every broken link, not just the first.
+    #[test]
+    fn tampered_previous_hash_fails_only_that_step() {
+        let params = CeremonyParams::<MNT6_753>::new(3);
+        let initial_hash = blank_hash();
+        let initial = Accumulator::<MNT6_753>::new(&params);
+
+        let mut step1 = honest_step(&initial, &initial_hash, &params);
+        let step2 = honest_step(&step1.accumulator, &step1.response_hash, &params);
+        step1.previous_hash[0] ^= 0xff;
+
+        let results = verify_transcript(&initial, initial_hash, &[step1, step2]);
+        assert_eq!(
+            results,
+            vec![Err(VerificationError::WrongPreviousHash), Ok(())]
+        );
+    }
+
+    /// A public key tampered with after the fact no longer proves knowledge
+    /// of the `tau` that actually produced the transformed accumulator.
+    #[test]
+    fn corrupted_public_key_fails_proof_of_knowledge() {
+        let params = CeremonyParams::<MNT6_753>::new(3);
+        let initial_hash = blank_hash();
+        let initial = Accumulator::<MNT6_753>::new(&params);
+
+        let mut step1 = honest_step(&initial, &initial_hash, &params);
+        step1.public_key.tau_g1.0 = step1.public_key.alpha_g1.0;
+
+        let results = verify_transcript(&initial, initial_hash, &[step1]);
+        assert_eq!(results, vec![Err(VerificationError::BadPokTau)]);
+    }
+
+    /// An accumulator tampered with at an index the proof-of-knowledge checks
+    /// don't look at (anything past `tau_powers_g1[1]`) still gets caught by
+    /// the `power_pairs` consistency check.
+    #[test]
+    fn corrupted_accumulator_fails_power_ratio_check() {
+        let params = CeremonyParams::<MNT6_753>::new(3);
+        let initial_hash = blank_hash();
+        let initial = Accumulator::<MNT6_753>::new(&params);
+
+        let mut step1 = honest_step(&initial, &initial_hash, &params);
+        let two = <MNT6_753 as Pairing>::ScalarField::one() + <MNT6_753 as Pairing>::ScalarField::one();
+        step1.accumulator.tau_powers_g1[2] =
+            (step1.accumulator.tau_powers_g1[2] * two).into_affine();
+
+        let results = verify_transcript(&initial, initial_hash, &[step1]);
+        assert_eq!(results, vec![Err(VerificationError::BadPowerRatio)]);
+    }
+}
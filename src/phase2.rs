@@ -0,0 +1,392 @@
+//! Phase 2: deriving circuit-specific Groth16 parameters from a finished
+//! phase 1 [`crate::Accumulator`], and running a second round of MPC
+//! contributions over the resulting proving key.
+//!
+//! Phase 1 only ever produces curve-independent powers of a shared secret
+//! `tau` (plus `alpha`/`beta`). Turning that into a usable Groth16 proving
+//! key requires evaluating the circuit's `A`/`B`/`C` query polynomials at
+//! `tau` -- without ever learning `tau` itself. We do this the same way the
+//! accumulator's own powers were produced: by applying the *same* linear
+//! operation (here, an inverse FFT over the evaluation domain the circuit's
+//! constraints live on) to the EC points in the accumulator instead of to
+//! `tau` directly, since scalar multiplication distributes over the linear
+//! combinations an IFFT performs.
+
+use crate::{hash_to_g2, same_ratio, Accumulator, CeremonyParams, HashWriter};
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{FftField, Field, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{One, UniformRand};
+use blake2::{Blake2b512, Digest};
+use num_traits::identities::Zero;
+use rand::Rng;
+
+/// A minimal view of an R1CS circuit, sized to a power-of-two number of
+/// constraints. `a`/`b`/`c` are dense, one row per constraint and one column
+/// per variable, matching the `A`/`B`/`C` matrices Groth16 derives its query
+/// vectors from.
+pub trait Circuit<F: PrimeField> {
+    /// Number of variables, i.e. the length of the `L` query.
+    fn num_variables(&self) -> usize;
+    /// Number of constraints. Must already be a power of two.
+    fn num_constraints(&self) -> usize;
+    fn a(&self) -> &[Vec<F>];
+    fn b(&self) -> &[Vec<F>];
+    fn c(&self) -> &[Vec<F>];
+}
+
+/// In-place radix-2 inverse FFT over elliptic curve points, mirroring the
+/// field-element IFFT a circuit's constraint system is otherwise evaluated
+/// with. This is what lets `tau_powers_g1`/`tau_powers_g2` (powers of `tau`
+/// in the monomial basis) be converted into the Lagrange basis -- i.e.
+/// `{L_i(tau) * G}` for the domain's roots of unity -- without ever
+/// recovering `tau`.
+fn group_ifft<G: AffineRepr>(points: &mut [G::Group])
+where
+    G::ScalarField: PrimeField,
+{
+    let n = points.len();
+    assert!(n.is_power_of_two(), "domain size must be a power of two");
+
+    // Bit-reversal permutation.
+    let log_n = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - log_n);
+        if i < j {
+            points.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let root = G::ScalarField::get_root_of_unity(len as u64)
+            .expect("domain size must divide the field's 2-adicity");
+        let root = root.inverse().expect("root of unity is never zero");
+
+        for chunk in points.chunks_mut(len) {
+            let mut w = G::ScalarField::one();
+            let half = len / 2;
+            for i in 0..half {
+                let t = chunk[i + half] * w;
+                let u = chunk[i];
+                chunk[i] = u + t;
+                chunk[i + half] = u - t;
+                w *= root;
+            }
+        }
+        len <<= 1;
+    }
+
+    let n_inv = G::ScalarField::from(n as u64)
+        .inverse()
+        .expect("domain size is never zero in the scalar field");
+    for p in points.iter_mut() {
+        *p *= n_inv;
+    }
+}
+
+/// Circuit-specific Groth16 parameters derived from a phase 1 accumulator.
+/// Mirrors (a subset of) `ark_groth16::ProvingKey`: the parts that come
+/// from the ceremony rather than from the circuit's public/private inputs.
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Phase2Parameters<P: Pairing> {
+    pub alpha_g1: P::G1Affine,
+    pub beta_g1: P::G1Affine,
+    pub beta_g2: P::G2Affine,
+    pub delta_g1: P::G1Affine,
+    pub delta_g2: P::G2Affine,
+    /// One element per variable: the delta-scaled linear combination of A/B/C
+    /// evaluated at tau for that variable.
+    pub l_query: Vec<P::G1Affine>,
+    /// One element per quotient-polynomial term: tau^i * (tau^n - 1) / delta.
+    pub h_query: Vec<P::G1Affine>,
+}
+
+/// Evaluates `circuit`'s `A`/`B`/`C` query polynomials at the ceremony's
+/// secret `tau` by applying [`group_ifft`] to the accumulator's powers of
+/// tau, then combining the resulting Lagrange-basis points per the
+/// circuit's constraint matrices. `delta` starts at 1 -- the first phase 2
+/// contributor's `Phase2Contribution` picks a fresh random delta.
+pub fn generate_parameters<P: Pairing>(
+    acc: &Accumulator<P>,
+    circuit: &impl Circuit<P::ScalarField>,
+    params: &CeremonyParams<P>,
+) -> Phase2Parameters<P>
+where
+    P::ScalarField: PrimeField,
+{
+    let m = circuit.num_constraints();
+    assert!(m.is_power_of_two(), "num_constraints must be a power of two");
+    assert!(
+        m <= params.tau_powers_length,
+        "circuit has more constraints than the ceremony supports"
+    );
+
+    // Powers of tau in the monomial basis, truncated to the circuit's domain
+    // size, converted into Lagrange basis (one point per constraint row) by
+    // `group_ifft`. `beta`/`alpha`-scaled copies are needed too, since the L
+    // query weights the A/B rows by beta/alpha respectively -- see below.
+    let mut tau_lagrange_g1: Vec<_> = acc.tau_powers_g1[0..m].iter().map(|p| p.into_group()).collect();
+    group_ifft::<P::G1Affine>(&mut tau_lagrange_g1);
+
+    let mut beta_lagrange_g1: Vec<_> = acc.beta_tau_powers_g1[0..m].iter().map(|p| p.into_group()).collect();
+    group_ifft::<P::G1Affine>(&mut beta_lagrange_g1);
+
+    let mut alpha_lagrange_g1: Vec<_> = acc.alpha_tau_powers_g1[0..m].iter().map(|p| p.into_group()).collect();
+    group_ifft::<P::G1Affine>(&mut alpha_lagrange_g1);
+
+    // L query: L_var(tau) = beta*A_var(tau) + alpha*B_var(tau) + C_var(tau),
+    // before dividing by delta (the division is applied by each phase 2
+    // contribution, see `contribute` below).
+    let num_vars = circuit.num_variables();
+    let mut l_query = vec![P::G1::zero(); num_vars];
+    for row in 0..m {
+        for (var, ((a, b), c)) in circuit.a()[row]
+            .iter()
+            .zip(circuit.b()[row].iter())
+            .zip(circuit.c()[row].iter())
+            .enumerate()
+        {
+            if !a.is_zero() {
+                l_query[var] += beta_lagrange_g1[row] * a;
+            }
+            if !b.is_zero() {
+                l_query[var] += alpha_lagrange_g1[row] * b;
+            }
+            if !c.is_zero() {
+                l_query[var] += tau_lagrange_g1[row] * c;
+            }
+        }
+    }
+    let l_query = P::G1::normalize_batch(&l_query);
+
+    // H query: tau^i * (tau^m - 1) = tau^(i+m) - tau^i, i = 0..m-1, before
+    // dividing by delta (the division is applied by each phase 2
+    // contribution, see `contribute` below).
+    let h_query: Vec<_> = (0..m)
+        .map(|i| acc.tau_powers_g1[i + m].into_group() - acc.tau_powers_g1[i].into_group())
+        .collect();
+    let h_query = P::G1::normalize_batch(&h_query);
+
+    Phase2Parameters {
+        alpha_g1: acc.alpha_tau_powers_g1[0],
+        beta_g1: acc.beta_tau_powers_g1[0],
+        beta_g2: acc.beta_g2,
+        delta_g1: P::G1Affine::generator(),
+        delta_g2: P::G2Affine::generator(),
+        l_query,
+        h_query,
+    }
+}
+
+/// Proof that a phase 2 contributor knows the discriminant `delta` they
+/// multiplied the previous `delta_g1`/`delta_g2` by, analogous to
+/// `PublicKey` in phase 1.
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct Phase2PublicKey<P: Pairing> {
+    delta_after: P::G1Affine,
+    delta_g1: (P::G1Affine, P::G1Affine),
+    delta_g2_s: P::G2Affine,
+}
+
+/// Applies one phase 2 contribution: multiplies `delta` by a fresh random
+/// scalar, rescaling `l_query`/`h_query` to match, and returns a proof of
+/// knowledge of the new delta chained to `transcript` (the BLAKE2b hash of
+/// the previous phase 2 parameters).
+pub fn contribute<P: Pairing, R: Rng>(
+    params: &mut Phase2Parameters<P>,
+    rng: &mut R,
+    transcript: &[u8; 64],
+) -> Phase2PublicKey<P> {
+    let delta = P::ScalarField::rand(rng);
+    let delta_inv = delta.inverse().expect("random scalar is never zero");
+
+    for p in params.l_query.iter_mut() {
+        *p = (*p * delta_inv).into_affine();
+    }
+    for p in params.h_query.iter_mut() {
+        *p = (*p * delta_inv).into_affine();
+    }
+    params.delta_g1 = (params.delta_g1 * delta).into_affine();
+    params.delta_g2 = (params.delta_g2 * delta).into_affine();
+
+    let g1_s = P::G1::rand(rng).into_affine();
+    let g1_s_delta = (g1_s * delta).into_affine();
+
+    let mut h = Blake2b512::default();
+    h.update(transcript);
+    g1_s.serialize_uncompressed(&mut h).unwrap();
+    g1_s_delta.serialize_uncompressed(&mut h).unwrap();
+    let delta_g2_s = hash_to_g2::<P>(h.finalize().as_ref()).into_affine();
+    let delta_g2_s = (delta_g2_s * delta).into_affine();
+
+    Phase2PublicKey {
+        delta_after: params.delta_g1,
+        delta_g1: (g1_s, g1_s_delta),
+        delta_g2_s,
+    }
+}
+
+/// Computes the BLAKE2b hash of `params`' `l_query`/`h_query`/delta, to feed
+/// the next contributor's `transcript` -- the phase 2 equivalent of chaining
+/// `HashWriter` hashes across phase 1 contributions.
+pub fn transcript_hash<P: Pairing>(params: &Phase2Parameters<P>) -> [u8; 64] {
+    let mut writer = HashWriter::new(Vec::new());
+    params.delta_g1.serialize_uncompressed(&mut writer).unwrap();
+    params.delta_g2.serialize_uncompressed(&mut writer).unwrap();
+    for p in &params.l_query {
+        p.serialize_uncompressed(&mut writer).unwrap();
+    }
+    for p in &params.h_query {
+        p.serialize_uncompressed(&mut writer).unwrap();
+    }
+    let mut out = [0u8; 64];
+    out.copy_from_slice(writer.into_hash().as_slice());
+    out
+}
+
+/// Verifies a phase 2 contribution, mirroring `verify_transform`: checks the
+/// proof of knowledge of the new delta, and that `after`'s query vectors are
+/// `before`'s rescaled by that same delta. `before == after` rescaled by any
+/// `delta != 1` must fail this check, or a contributor could "contribute"
+/// without actually mixing in fresh randomness.
+pub fn phase2_verify<P: Pairing>(
+    before: &Phase2Parameters<P>,
+    after: &Phase2Parameters<P>,
+    key: &Phase2PublicKey<P>,
+    transcript: &[u8; 64],
+) -> bool {
+    let mut h = Blake2b512::default();
+    h.update(transcript);
+    key.delta_g1.0.serialize_uncompressed(&mut h).unwrap();
+    key.delta_g1.1.serialize_uncompressed(&mut h).unwrap();
+    let delta_g2_s = hash_to_g2::<P>(h.finalize().as_ref()).into_affine();
+
+    if !same_ratio::<P>(key.delta_g1, (delta_g2_s, key.delta_g2_s)) {
+        return false;
+    }
+    if !same_ratio::<P>(
+        (before.delta_g1, after.delta_g1),
+        (delta_g2_s, key.delta_g2_s),
+    ) {
+        return false;
+    }
+    if after.delta_g1 != key.delta_after {
+        return false;
+    }
+
+    // `after`'s queries are `before`'s divided by the fresh delta, i.e.
+    // before = after * delta, which `same_ratio` checks via the delta_g2 pair.
+    for (b, a) in before.l_query.iter().zip(after.l_query.iter()) {
+        if !same_ratio::<P>((*a, *b), (before.delta_g2, after.delta_g2)) {
+            return false;
+        }
+    }
+    for (b, a) in before.h_query.iter().zip(after.h_query.iter()) {
+        if !same_ratio::<P>((*a, *b), (before.delta_g2, after.delta_g2)) {
+            return false;
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keypair;
+    use ark_mnt6_753::{Fr, MNT6_753};
+    use ark_std::One;
+    use rand::thread_rng;
+
+    /// Mirrors `phase2_contribute`'s `IdentityCircuit`: row `i` asserts
+    /// `variable[i] * 0 = 0` -- not useful for proving anything, but a real
+    /// power-of-two domain with dense A/B/C rows, enough to exercise
+    /// `generate_parameters`'s IFFT and query combination end to end.
+    struct IdentityCircuit {
+        a: Vec<Vec<Fr>>,
+        b: Vec<Vec<Fr>>,
+        c: Vec<Vec<Fr>>,
+    }
+
+    impl IdentityCircuit {
+        fn new(size: usize) -> Self {
+            assert!(size.is_power_of_two());
+            let mut a = vec![vec![Fr::from(0u64); size]; size];
+            for (i, row) in a.iter_mut().enumerate() {
+                row[i] = Fr::one();
+            }
+            let b = vec![vec![Fr::from(0u64); size]; size];
+            let c = vec![vec![Fr::from(0u64); size]; size];
+            IdentityCircuit { a, b, c }
+        }
+    }
+
+    impl Circuit<Fr> for IdentityCircuit {
+        fn num_variables(&self) -> usize {
+            self.a[0].len()
+        }
+
+        fn num_constraints(&self) -> usize {
+            self.a.len()
+        }
+
+        fn a(&self) -> &[Vec<Fr>] {
+            &self.a
+        }
+
+        fn b(&self) -> &[Vec<Fr>] {
+            &self.b
+        }
+
+        fn c(&self) -> &[Vec<Fr>] {
+            &self.c
+        }
+    }
+
+    /// `generate_parameters` -> `contribute` -> `phase2_verify` end to end:
+    /// an honest contribution over a freshly transformed phase 1 accumulator
+    /// must verify.
+    #[test]
+    fn honest_contribution_verifies() {
+        let params = CeremonyParams::<MNT6_753>::new(3);
+        let circuit = IdentityCircuit::new(4);
+
+        let (_, phase1_key) = keypair::<MNT6_753, _>(&mut thread_rng(), &[0u8; 64]);
+        let mut acc = Accumulator::new(&params);
+        acc.transform(&phase1_key, &params);
+
+        let before = generate_parameters(&acc, &circuit, &params);
+        let transcript = transcript_hash(&before);
+
+        let mut after = before.clone();
+        let public_key = contribute(&mut after, &mut thread_rng(), &transcript);
+
+        assert!(phase2_verify(&before, &after, &public_key, &transcript));
+    }
+
+    /// A contribution's public key proves knowledge of the delta that
+    /// produced *a* rescaling of `before` -- it must not verify against a
+    /// different, unrelated rescaling claiming to be `after`.
+    #[test]
+    fn contribution_does_not_verify_against_a_different_rescaling() {
+        let params = CeremonyParams::<MNT6_753>::new(3);
+        let circuit = IdentityCircuit::new(4);
+
+        let (_, phase1_key) = keypair::<MNT6_753, _>(&mut thread_rng(), &[0u8; 64]);
+        let mut acc = Accumulator::new(&params);
+        acc.transform(&phase1_key, &params);
+
+        let before = generate_parameters(&acc, &circuit, &params);
+        let transcript = transcript_hash(&before);
+
+        let mut after = before.clone();
+        let public_key = contribute(&mut after, &mut thread_rng(), &transcript);
+
+        let mut other_after = before.clone();
+        let _ = contribute(&mut other_after, &mut thread_rng(), &transcript);
+
+        assert!(!phase2_verify(&before, &other_after, &public_key, &transcript));
+    }
+}